@@ -0,0 +1,80 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use aleo_record::*;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaChaRng;
+use snarkvm_curves::traits::AffineCurve;
+use snarkvm_utilities::{to_bytes, ToBytes, UniformRand};
+
+pub const SEED: u64 = 1231275789u64;
+
+// The payload sizes to benchmark. A 31-byte payload leaves a 248-bit tail, which forces
+// the `value_does_not_fit` extra element, so the boundary case is covered.
+pub const PAYLOAD_SIZES: [usize; 6] = [0, 31, 32, 128, 512, 2048];
+
+fn sample_record(rng: &mut ChaChaRng, payload_len: usize) -> Record {
+    let serial_number_nonce = loop {
+        let candidate = SerialNumberNonce::rand(rng);
+        if Affine::from_random_bytes(&to_bytes![candidate].unwrap()).is_some() {
+            break candidate;
+        }
+    };
+
+    let mut payload_bytes = vec![0u8; payload_len];
+    rng.fill_bytes(&mut payload_bytes);
+
+    Record {
+        owner: vec![0u8; 32],
+        value: rng.gen(),
+        payload: Payload::from_bytes(&payload_bytes),
+        birth_program_id: to_bytes![OuterField::rand(rng)].unwrap(),
+        death_program_id: to_bytes![OuterField::rand(rng)].unwrap(),
+        serial_number_nonce,
+        commitment: vec![0u8; 32],
+        commitment_randomness: CommitmentRandomness::rand(rng),
+    }
+}
+
+fn record_serialize(c: &mut Criterion) {
+    let rng = &mut ChaChaRng::seed_from_u64(SEED);
+
+    for payload_len in PAYLOAD_SIZES {
+        let record = sample_record(rng, payload_len);
+
+        c.bench_function(&format!("serialize_payload_{}", payload_len), |b| {
+            b.iter(|| RecordEncoder::serialize(&record).unwrap())
+        });
+    }
+}
+
+fn record_deserialize(c: &mut Criterion) {
+    let rng = &mut ChaChaRng::seed_from_u64(SEED);
+
+    for payload_len in PAYLOAD_SIZES {
+        let record = sample_record(rng, payload_len);
+        let (serialized_record, final_sign_high) = RecordEncoder::serialize(&record).unwrap();
+
+        c.bench_function(&format!("deserialize_payload_{}", payload_len), |b| {
+            b.iter(|| RecordEncoder::deserialize(&serialized_record, final_sign_high).unwrap())
+        });
+    }
+}
+
+criterion_group!(record_encoder, record_serialize, record_deserialize);
+criterion_main!(record_encoder);
@@ -0,0 +1,51 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+#![no_main]
+
+use aleo_record::*;
+
+use libfuzzer_sys::fuzz_target;
+use snarkvm_curves::traits::AffineCurve;
+use snarkvm_utilities::FromBytes;
+
+// The byte size of one uncompressed affine group element (two 32-byte coordinates).
+const ELEMENT_BYTES: usize = 64;
+
+fuzz_target!(|data: &[u8]| {
+    if data.is_empty() {
+        return;
+    }
+    let final_sign_high = data[0] & 1 == 1;
+
+    // Interpret the remaining bytes as a sequence of affine group elements. Chunks that
+    // do not parse as curve points are skipped, so the fuzzer explores valid points.
+    let mut elements = vec![];
+    for chunk in data[1..].chunks_exact(ELEMENT_BYTES) {
+        if let Ok(element) = Affine::read(chunk) {
+            elements.push(element.into_projective());
+        }
+    }
+
+    // Deserializing arbitrary group-element sequences must return a `Result`, not panic;
+    // empty and short inputs are rejected with typed errors.
+    let _ = RecordEncoder::deserialize(&elements, final_sign_high);
+
+    // Also exercise a truncated version of the same sequence.
+    if elements.len() > 6 {
+        let _ = RecordEncoder::deserialize(&elements[..elements.len() - 1], final_sign_high);
+    }
+});
@@ -0,0 +1,134 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+//! A debugging tool that encodes a JSON record into its hex-serialized form and back.
+//!
+//! `aleo-record encode record.json` reads a JSON record and prints the serialized group
+//! element bytes plus the final sign byte as one hex string; `aleo-record decode
+//! record.hex` inverts it. The JSON shape mirrors `DecodedRecord`, with byte fields in
+//! hex.
+
+use aleo_record::{Affine, CommitmentRandomness, DPCError, Payload, Record, RecordEncoder, SerialNumberNonce};
+
+use serde::{Deserialize, Serialize};
+use snarkvm_curves::traits::ProjectiveCurve;
+use snarkvm_utilities::{to_bytes, FromBytes, ToBytes};
+
+/// The JSON shape of a record: the fields of `DecodedRecord`, with byte fields in hex.
+#[derive(Deserialize, Serialize)]
+struct RecordJson {
+    value: u64,
+    payload: String,
+    birth_program_id: String,
+    death_program_id: String,
+    serial_number_nonce: String,
+    commitment_randomness: String,
+}
+
+fn hex_field(name: &'static str, hex: &str) -> Result<Vec<u8>, DPCError> {
+    hex::decode(hex).map_err(|error| DPCError::Message(format!("the {} field is not valid hex: {}", name, error)))
+}
+
+fn encode(json: &str) -> Result<String, DPCError> {
+    let record: RecordJson =
+        serde_json::from_str(json).map_err(|error| DPCError::Message(format!("invalid record JSON: {}", error)))?;
+
+    // The owner and commitment are not part of the encoded form, so they are left empty.
+    let record = Record {
+        owner: vec![],
+        value: record.value,
+        payload: Payload::from_bytes(&hex_field("payload", &record.payload)?),
+        birth_program_id: hex_field("birth_program_id", &record.birth_program_id)?,
+        death_program_id: hex_field("death_program_id", &record.death_program_id)?,
+        serial_number_nonce: SerialNumberNonce::read(&hex_field("serial_number_nonce", &record.serial_number_nonce)?[..])?,
+        commitment: vec![],
+        commitment_randomness: CommitmentRandomness::read(
+            &hex_field("commitment_randomness", &record.commitment_randomness)?[..],
+        )?,
+    };
+
+    let (serialized_record, final_sign_high) = RecordEncoder::serialize(&record)?;
+    let mut bytes = vec![];
+    for element in serialized_record.iter() {
+        bytes.extend_from_slice(&to_bytes![element.into_affine()]?);
+    }
+    bytes.push(final_sign_high as u8);
+
+    Ok(hex::encode(bytes))
+}
+
+fn decode(hex: &str) -> Result<String, DPCError> {
+    let bytes = hex_field("record", hex.trim())?;
+    if bytes.is_empty() || (bytes.len() - 1) % aleo_record::ELEMENT_BYTES != 0 {
+        return Err(DPCError::Message(format!(
+            "expected a final sign byte after a multiple of {} element bytes, found {} bytes",
+            aleo_record::ELEMENT_BYTES,
+            bytes.len()
+        )));
+    }
+
+    let final_sign_high = match bytes[bytes.len() - 1] {
+        0 => false,
+        1 => true,
+        byte => return Err(DPCError::Message(format!("invalid final sign byte {}", byte))),
+    };
+
+    let mut serialized_record = vec![];
+    for chunk in bytes[..bytes.len() - 1].chunks(aleo_record::ELEMENT_BYTES) {
+        serialized_record.push(Affine::read(chunk)?.into_projective());
+    }
+
+    let decoded = RecordEncoder::deserialize(&serialized_record, final_sign_high)?;
+    let json = RecordJson {
+        value: decoded.value,
+        payload: hex::encode(decoded.payload.as_ref()),
+        birth_program_id: hex::encode(&decoded.birth_program_id),
+        death_program_id: hex::encode(&decoded.death_program_id),
+        serial_number_nonce: hex::encode(to_bytes![decoded.serial_number_nonce]?),
+        commitment_randomness: hex::encode(to_bytes![decoded.commitment_randomness]?),
+    };
+
+    serde_json::to_string_pretty(&json).map_err(|error| DPCError::Message(error.to_string()))
+}
+
+fn run() -> Result<String, DPCError> {
+    let args: Vec<String> = std::env::args().collect();
+    let (mode, path) = match &args[..] {
+        [_, mode, path] => (mode.as_str(), path),
+        _ => {
+            return Err(DPCError::Message(
+                "usage: aleo-record <encode|decode> <file>".to_string(),
+            ));
+        }
+    };
+
+    let input = std::fs::read_to_string(path)?;
+    match mode {
+        "encode" => encode(&input),
+        "decode" => decode(&input),
+        mode => Err(DPCError::Message(format!("unknown mode `{}`", mode))),
+    }
+}
+
+fn main() {
+    match run() {
+        Ok(output) => println!("{}", output),
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    }
+}
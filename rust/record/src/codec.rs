@@ -0,0 +1,77 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+    decoded::DecodedRecord,
+    encoder::{Affine, RecordEncoder},
+    errors::{DPCError, RecordError},
+    packed::ELEMENT_BYTES,
+    record::Record,
+};
+
+use snarkvm_curves::traits::ProjectiveCurve;
+use snarkvm_utilities::{to_bytes, FromBytes, ToBytes};
+
+/// An object-safe record codec, so the encoding curve can be chosen at runtime from a
+/// network parameter and held as a `Box<dyn RecordCodec>`.
+///
+/// The group elements are erased into bytes at the trait boundary, since the element
+/// type differs per curve: a serialized record is the concatenated affine bytes of its
+/// elements followed by the final sign bit as one byte.
+pub trait RecordCodec {
+    /// Serializes the record into the codec's byte framing.
+    fn serialize_bytes(&self, record: &Record) -> Result<Vec<u8>, DPCError>;
+
+    /// Decodes a record from the codec's byte framing.
+    fn deserialize_bytes(&self, bytes: &[u8]) -> Result<DecodedRecord, DPCError>;
+}
+
+/// The `RecordCodec` for the default Edwards BLS12 encoding group.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EdwardsBlsCodec;
+
+impl RecordCodec for EdwardsBlsCodec {
+    fn serialize_bytes(&self, record: &Record) -> Result<Vec<u8>, DPCError> {
+        let (serialized_record, final_sign_high) = RecordEncoder::serialize_affine(record)?;
+
+        let mut bytes = Vec::with_capacity(serialized_record.len() * ELEMENT_BYTES + 1);
+        for element in serialized_record.iter() {
+            bytes.extend_from_slice(&to_bytes![element]?);
+        }
+        bytes.push(final_sign_high as u8);
+
+        Ok(bytes)
+    }
+
+    fn deserialize_bytes(&self, bytes: &[u8]) -> Result<DecodedRecord, DPCError> {
+        if bytes.is_empty() || (bytes.len() - 1) % ELEMENT_BYTES != 0 {
+            return Err(RecordError::ShortSerialization(bytes.len() / ELEMENT_BYTES).into());
+        }
+
+        let final_sign_high = match bytes[bytes.len() - 1] {
+            0 => false,
+            1 => true,
+            byte => return Err(DPCError::Message(format!("invalid final sign byte {}", byte))),
+        };
+
+        let mut serialized_record = vec![];
+        for chunk in bytes[..bytes.len() - 1].chunks(ELEMENT_BYTES) {
+            serialized_record.push(Affine::read(chunk)?.into_projective());
+        }
+
+        RecordEncoder::deserialize(&serialized_record, final_sign_high)
+    }
+}
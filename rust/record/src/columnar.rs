@@ -0,0 +1,52 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::record::{CommitmentRandomness, SerialNumberNonce};
+
+/// A batch of decoded records laid out column-wise, for analytics workloads that scan
+/// one field across many records without touching the others.
+///
+/// Fixed-width columns are flat `Vec`s indexed by record position; the program id
+/// columns are flat byte vectors with one fixed-width id per record; the variable-width
+/// payload column is a flat byte vector with an offsets table of `len() + 1` entries, so
+/// record `i`'s payload is `payload_bytes[payload_offsets[i]..payload_offsets[i + 1]]`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ColumnarRecords {
+    pub values: Vec<u64>,
+    pub payload_bytes: Vec<u8>,
+    pub payload_offsets: Vec<usize>,
+    pub birth_program_id_bytes: Vec<u8>,
+    pub death_program_id_bytes: Vec<u8>,
+    pub serial_number_nonces: Vec<SerialNumberNonce>,
+    pub commitment_randomness: Vec<CommitmentRandomness>,
+}
+
+impl ColumnarRecords {
+    /// Returns the number of records in the batch.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if the batch holds no records.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the payload bytes of record `i`.
+    pub fn payload(&self, i: usize) -> &[u8] {
+        &self.payload_bytes[self.payload_offsets[i]..self.payload_offsets[i + 1]]
+    }
+}
@@ -0,0 +1,555 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+    encoder::RecordEncoder,
+    errors::{DPCError, RecordError},
+    payload::Payload,
+    record::{CommitmentRandomness, InnerField, Record, SerialNumberNonce},
+};
+
+use rand::Rng;
+use snarkvm_fields::PrimeField;
+use snarkvm_utilities::{bits_to_bytes, bytes_to_bits, to_bytes, BigInteger, FromBytes, ToBytes, UniformRand};
+
+/// The BLAKE2s domain separator for `DecodedRecord::program_id_pair_hash`.
+const PROGRAM_ID_PAIR_DOMAIN: &[u8] = b"AleoRecordProgramIdPair";
+
+/// The fields of a record recovered by `RecordEncoder::deserialize`.
+///
+/// The owner and commitment are not part of the encoded form, so decoding never
+/// produces them; the optional `owner` field exists so callers who already know the
+/// owner can carry it alongside the decoded fields via [`with_owner`](Self::with_owner)
+/// instead of in parallel bookkeeping. It stays `None` unless attached.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedRecord {
+    pub value: u64,
+    pub payload: Payload,
+    pub birth_program_id: Vec<u8>,
+    pub death_program_id: Vec<u8>,
+    pub serial_number_nonce: SerialNumberNonce,
+    pub commitment_randomness: CommitmentRandomness,
+    /// The owner bytes attached by `with_owner`; never read or written by the encoder.
+    pub owner: Option<Vec<u8>>,
+}
+
+impl DecodedRecord {
+    /// Attaches the given owner bytes to the record, consuming and returning it, so a
+    /// decode can be paired with its known owner in one expression.
+    pub fn with_owner(mut self, owner: Vec<u8>) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    /// Returns the attached owner bytes, if any.
+    pub fn owner(&self) -> Option<&[u8]> {
+        self.owner.as_deref()
+    }
+
+    /// Returns the record's value.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// Returns an error if the record's value exceeds `max`, for callers that treat
+    /// values as token amounts under a supply cap.
+    pub fn validate_value(&self, max: u64) -> Result<(), DPCError> {
+        if self.value > max {
+            return Err(DPCError::ValueOutOfRange(self.value, max));
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the record's payload is empty or all zeros.
+    pub fn has_empty_payload(&self) -> bool {
+        self.payload.is_zero()
+    }
+
+    /// Overwrites the payload bytes at `[offset..offset + data.len()]` with `data`,
+    /// leaving the payload length unchanged. Errors if the write falls out of bounds.
+    pub fn patch_payload(&mut self, offset: usize, data: &[u8]) -> Result<(), DPCError> {
+        self.payload.patch(offset, data)
+    }
+
+    /// Strips trailing zero bytes from the payload, leaving every other field unchanged.
+    ///
+    /// Records that differ only in trailing zero padding carry the same data, so this
+    /// produces a stable canonical form for deduplication. Only whole bytes are
+    /// stripped — the payload terminator bit is positional, not stored in the payload —
+    /// and a shorter payload is always encodable, so the canonical form re-serializes.
+    pub fn canonicalize(&mut self) {
+        let end = self.payload.as_ref().iter().rposition(|byte| *byte != 0).map_or(0, |index| index + 1);
+        self.payload.truncate(end);
+    }
+
+    /// Returns `true` if this record and `other` are equal after canonicalization,
+    /// without modifying either record.
+    pub fn canonical_eq(&self, other: &Self) -> bool {
+        let mut this = self.clone();
+        let mut other = other.clone();
+        this.canonicalize();
+        other.canonicalize();
+        this == other
+    }
+
+    /// Returns the canonical bytes of the serial number nonce, as consumed by the
+    /// serial-number derivation and by `serialize`.
+    pub fn serial_number_nonce_bytes(&self) -> Result<Vec<u8>, DPCError> {
+        Ok(to_bytes![self.serial_number_nonce]?)
+    }
+
+    /// Replaces the commitment randomness with a freshly sampled value, leaving every
+    /// other field unchanged.
+    ///
+    /// The sample is drawn uniformly from the scalar field, so by Assumption 1 it always
+    /// fits the inner field and survives `encode_to_group`. Derived records that reuse
+    /// another record's randomness are linkable through it; this breaks that link.
+    pub fn rerandomize_commitment<R: Rng>(&mut self, rng: &mut R) {
+        self.commitment_randomness = CommitmentRandomness::rand(rng);
+    }
+
+    /// Returns `(index, old_byte, new_byte)` for every payload byte that differs between
+    /// this record and `other`, in index order.
+    ///
+    /// Both payloads must be the same length, since the diff is positional; auditing a
+    /// state transition that resized the payload needs a different comparison.
+    pub fn payload_diff(&self, other: &Self) -> Result<Vec<(usize, u8, u8)>, DPCError> {
+        if self.payload.len() != other.payload.len() {
+            return Err(DPCError::Message(format!(
+                "cannot diff payloads of {} and {} bytes",
+                self.payload.len(),
+                other.payload.len()
+            )));
+        }
+
+        Ok(self
+            .payload
+            .as_ref()
+            .iter()
+            .zip(other.payload.as_ref())
+            .enumerate()
+            .filter(|(_, (old, new))| old != new)
+            .map(|(index, (old, new))| (index, *old, *new))
+            .collect())
+    }
+
+    /// Derives the record's serial number from the owner's `sk_prf` key bytes,
+    /// evaluating the same PRF over the same inputs as the DPC's `generate_sn`:
+    /// BLAKE2s seeded by `sk_prf` over the serial number nonce bytes, with no extra
+    /// domain separation, so the output matches what `snarkvm-dpc` 0.7.5 computes at
+    /// its PRF layer for the same key and nonce.
+    ///
+    /// `sk_prf` is the PRF sub-key of the account private key — not the full private
+    /// key — and must be exactly 32 bytes, the PRF seed width.
+    pub fn serial_number(&self, sk_prf_bytes: &[u8]) -> Result<Vec<u8>, DPCError> {
+        if sk_prf_bytes.len() != 32 {
+            return Err(DPCError::Message(format!(
+                "the sk_prf key is {} bytes, but the serial number PRF requires 32 bytes",
+                sk_prf_bytes.len()
+            )));
+        }
+        let mut seed = [0u8; 32];
+        seed.copy_from_slice(sk_prf_bytes);
+
+        Ok(crate::encoder::blake2s_hash(&seed, &to_bytes![self.serial_number_nonce]?)?.to_vec())
+    }
+
+    /// Computes a stable 32-byte identifier for the record's `(birth_program_id,
+    /// death_program_id)` pair: BLAKE2s over the concatenated ids under a fixed
+    /// domain-separation seed.
+    ///
+    /// Indexers that key records by their program pair can use this directly instead of
+    /// hashing the concatenation in application code. The domain separator keeps these
+    /// digests disjoint from `local_commitment_for` and `content_hash` outputs.
+    pub fn program_id_pair_hash(&self) -> Result<[u8; 32], DPCError> {
+        let mut seed = [0u8; 32];
+        seed[..PROGRAM_ID_PAIR_DOMAIN.len()].copy_from_slice(PROGRAM_ID_PAIR_DOMAIN);
+
+        let mut input = Vec::with_capacity(self.birth_program_id.len() + self.death_program_id.len());
+        input.extend_from_slice(&self.birth_program_id);
+        input.extend_from_slice(&self.death_program_id);
+
+        crate::encoder::blake2s_hash(&seed, &input)
+    }
+
+    /// Assembles the canonical byte layout that feeds the crate-local record commitment.
+    ///
+    /// The fields are concatenated in the order the commitment scheme expects: value,
+    /// payload, birth program id, death program id, serial number nonce, owner. The owner
+    /// is supplied by the caller, since it is not part of the decoded form.
+    pub fn commitment_preimage(&self, owner: &[u8]) -> Result<Vec<u8>, DPCError> {
+        let mut preimage = vec![];
+        preimage.extend_from_slice(&to_bytes![self.value]?);
+        preimage.extend_from_slice(self.payload.as_ref());
+        preimage.extend_from_slice(&self.birth_program_id);
+        preimage.extend_from_slice(&self.death_program_id);
+        preimage.extend_from_slice(&to_bytes![self.serial_number_nonce]?);
+        preimage.extend_from_slice(owner);
+        Ok(preimage)
+    }
+
+    /// Renders the record in the brace-delimited plaintext format used by newer Aleo
+    /// tooling, with the value under its `gates` field name and byte fields in `0x` hex.
+    ///
+    /// The owner is not stored in `DecodedRecord`, so it is supplied by the caller as
+    /// the address string to embed.
+    pub fn to_plaintext(&self, owner: &str) -> Result<String, DPCError> {
+        Ok(format!(
+            "{{\n  owner: {},\n  gates: {}u64,\n  payload: {},\n  birth_program_id: {},\n  death_program_id: {},\n  serial_number_nonce: {},\n  commitment_randomness: {}\n}}",
+            owner,
+            self.value,
+            to_hex(self.payload.as_ref()),
+            to_hex(&self.birth_program_id),
+            to_hex(&self.death_program_id),
+            to_hex(&to_bytes![self.serial_number_nonce]?),
+            to_hex(&to_bytes![self.commitment_randomness]?)
+        ))
+    }
+
+    /// Parses a record from the brace-delimited plaintext format emitted by
+    /// `to_plaintext`, returning the decoded record and the owner string separately.
+    ///
+    /// Malformed input errors name the offending field.
+    pub fn from_plaintext(s: &str) -> Result<(DecodedRecord, String), DPCError> {
+        let inner = s
+            .trim()
+            .strip_prefix('{')
+            .and_then(|inner| inner.strip_suffix('}'))
+            .ok_or_else(|| DPCError::Message("the record plaintext is not brace-delimited".to_string()))?;
+
+        let mut fields = std::collections::HashMap::new();
+        for entry in inner.split(',') {
+            let entry = entry.trim();
+            if entry.is_empty() {
+                continue;
+            }
+            let (name, value) = entry
+                .split_once(':')
+                .ok_or_else(|| DPCError::Message(format!("the record plaintext entry `{}` has no value", entry)))?;
+            fields.insert(name.trim().to_string(), value.trim().to_string());
+        }
+
+        let field = |name: &str| -> Result<&String, DPCError> {
+            fields
+                .get(name)
+                .ok_or_else(|| DPCError::Message(format!("the record plaintext is missing the `{}` field", name)))
+        };
+        let hex_field = |name: &str| -> Result<Vec<u8>, DPCError> {
+            from_hex(field(name)?)
+                .ok_or_else(|| DPCError::Message(format!("the `{}` field is not a valid hex string", name)))
+        };
+
+        let owner = field("owner")?.clone();
+        let value = field("gates")?
+            .strip_suffix("u64")
+            .and_then(|gates| gates.parse::<u64>().ok())
+            .ok_or_else(|| DPCError::Message("the `gates` field is not a `u64` literal".to_string()))?;
+        let serial_number_nonce = SerialNumberNonce::read(&hex_field("serial_number_nonce")?[..])
+            .map_err(|_| DPCError::Message("the `serial_number_nonce` field is not a field element".to_string()))?;
+        let commitment_randomness = CommitmentRandomness::read(&hex_field("commitment_randomness")?[..])
+            .map_err(|_| DPCError::Message("the `commitment_randomness` field is not a scalar".to_string()))?;
+
+        Ok((
+            DecodedRecord {
+                value,
+                payload: Payload::from_bytes(&hex_field("payload")?),
+                birth_program_id: hex_field("birth_program_id")?,
+                death_program_id: hex_field("death_program_id")?,
+                serial_number_nonce,
+                commitment_randomness,
+                owner: None,
+            },
+            owner,
+        ))
+    }
+
+    /// Flattens the record into inner field elements, in the same order `serialize`
+    /// consumes the fields:
+    ///
+    /// 1. the serial number nonce;
+    /// 2. the commitment randomness;
+    /// 3. the low bits of the birth program id;
+    /// 4. the low bits of the death program id;
+    /// 5. the remainder bits of both program ids, birth first;
+    /// 6. one element per `PAYLOAD_ELEMENT_BITSIZE` payload bits, each terminated by a
+    ///    reserved `1` bit;
+    /// 7. when the payload tail cannot share an element with the value bits, the
+    ///    terminated tail on its own, mirroring `serialize`'s `value_does_not_fit`
+    ///    flush;
+    /// 8. the value bits followed by the terminated payload tail (empty after a flush).
+    ///
+    /// The per-element sign bits are excluded, since they are a property of the group
+    /// encoding rather than of the record itself. This order is stable, so circuits and
+    /// host code can rely on it.
+    pub fn to_field_elements(&self) -> Result<Vec<InnerField>, DPCError> {
+        let field_from_bits =
+            |bits: &[bool]| -> Result<InnerField, DPCError> { Ok(InnerField::read(&bits_to_bytes(bits)[..])?) };
+
+        let mut elements = vec![self.serial_number_nonce];
+
+        // The commitment randomness bits fit the inner field by Assumption 1.
+        let commitment_randomness_bytes = to_bytes![self.commitment_randomness]?;
+        elements.push(InnerField::read(&commitment_randomness_bytes[..])?);
+
+        // Split the program ids exactly as `serialize` does.
+        let birth_program_id_biginteger = crate::encoder::read_program_id(&self.birth_program_id)?.into_repr();
+        let death_program_id_biginteger = crate::encoder::read_program_id(&self.death_program_id)?.into_repr();
+
+        let mut birth_program_id_bits = vec![];
+        let mut death_program_id_bits = vec![];
+        let mut program_id_remainder_bits = vec![];
+        for i in 0..RecordEncoder::OUTER_FIELD_BITSIZE {
+            if i < RecordEncoder::DATA_ELEMENT_BITSIZE {
+                birth_program_id_bits.push(birth_program_id_biginteger.get_bit(i));
+                death_program_id_bits.push(death_program_id_biginteger.get_bit(i));
+            } else {
+                program_id_remainder_bits.push(birth_program_id_biginteger.get_bit(i));
+            }
+        }
+        for i in RecordEncoder::DATA_ELEMENT_BITSIZE..RecordEncoder::OUTER_FIELD_BITSIZE {
+            program_id_remainder_bits.push(death_program_id_biginteger.get_bit(i));
+        }
+
+        elements.push(field_from_bits(&birth_program_id_bits)?);
+        elements.push(field_from_bits(&death_program_id_bits)?);
+        elements.push(field_from_bits(&program_id_remainder_bits)?);
+
+        // Pack the payload into terminated elements, exactly as `serialize` does.
+        let payload_bits = bytes_to_bits(self.payload.as_ref());
+        let mut payload_field_bits = Vec::with_capacity(RecordEncoder::PAYLOAD_ELEMENT_BITSIZE + 1);
+        for (i, bit) in payload_bits.iter().enumerate() {
+            payload_field_bits.push(*bit);
+            if (i + 1) % RecordEncoder::PAYLOAD_ELEMENT_BITSIZE == 0 {
+                payload_field_bits.push(true);
+                elements.push(field_from_bits(&payload_field_bits)?);
+                payload_field_bits.clear();
+            }
+        }
+
+        // The last element holds the value bits and the terminated payload tail. A tail
+        // longer than `DATA_ELEMENT_BITSIZE - VALUE_BITSIZE - 1` bits would overflow the
+        // element, so, as in `serialize`, it is flushed into one extra terminated
+        // element first, leaving the final element's tail empty.
+        let mut final_bits = bytes_to_bits(&to_bytes![self.value]?);
+        if final_bits.len() + payload_field_bits.len() + 1 > RecordEncoder::DATA_ELEMENT_BITSIZE {
+            payload_field_bits.push(true);
+            elements.push(field_from_bits(&payload_field_bits)?);
+            payload_field_bits.clear();
+        }
+        final_bits.append(&mut payload_field_bits);
+        final_bits.push(true);
+        elements.push(field_from_bits(&final_bits)?);
+
+        Ok(elements)
+    }
+}
+
+/// The per-field outcome of `RecordEncoder::deserialize_lenient`.
+///
+/// Each field decodes independently, so a corrupted program id does not block reading
+/// the value or payload. This exists for forensic inspection of damaged records; sound
+/// records should go through `deserialize`, which fails as a whole.
+#[derive(Debug)]
+pub struct PartialDecodedRecord {
+    pub value: Result<u64, DPCError>,
+    pub payload: Result<Payload, DPCError>,
+    pub birth_program_id: Result<Vec<u8>, DPCError>,
+    pub death_program_id: Result<Vec<u8>, DPCError>,
+    pub serial_number_nonce: Result<SerialNumberNonce, DPCError>,
+    pub commitment_randomness: Result<CommitmentRandomness, DPCError>,
+}
+
+impl DecodedRecord {
+    /// Rebuilds a record from the inner field elements produced by `to_field_elements`,
+    /// validating the element count against the payload length the elements imply.
+    ///
+    /// This closes the loop with a circuit's public inputs: a record passed through a
+    /// proof can be reconstructed on the verifier side from the same flattening.
+    pub fn from_field_elements(elements: &[InnerField]) -> Result<DecodedRecord, DPCError> {
+        if elements.len() < 6 {
+            return Err(RecordError::ShortSerialization(elements.len()).into());
+        }
+
+        let element_bits = |element: &InnerField| -> Result<Vec<bool>, DPCError> {
+            Ok(bytes_to_bits(&to_bytes![element]?))
+        };
+
+        let serial_number_nonce = elements[0];
+
+        let commitment_randomness_bits = element_bits(&elements[1])?;
+        let commitment_randomness: CommitmentRandomness =
+            FromBytes::read(&bits_to_bytes(&commitment_randomness_bits[..RecordEncoder::SCALAR_FIELD_BITSIZE])[..])?;
+
+        // Reassemble the program ids from their low bits and the shared remainder.
+        let remainder_size = RecordEncoder::OUTER_FIELD_BITSIZE - RecordEncoder::DATA_ELEMENT_BITSIZE;
+        let remainder_bits = element_bits(&elements[4])?;
+
+        let mut birth_program_id_bits = element_bits(&elements[2])?[..RecordEncoder::DATA_ELEMENT_BITSIZE].to_vec();
+        birth_program_id_bits.extend_from_slice(&remainder_bits[..remainder_size]);
+        let birth_program_id = bits_to_bytes(&birth_program_id_bits);
+
+        let mut death_program_id_bits = element_bits(&elements[3])?[..RecordEncoder::DATA_ELEMENT_BITSIZE].to_vec();
+        death_program_id_bits.extend_from_slice(&remainder_bits[remainder_size..2 * remainder_size]);
+        let death_program_id = bits_to_bytes(&death_program_id_bits);
+
+        // Each payload element's terminator must sit at the element boundary, except
+        // the last one, which may be a flushed tail terminating early; the tail in the
+        // final element follows the value bits.
+        let mut payload_bits = vec![];
+        let payload_elements = &elements[5..elements.len() - 1];
+        let mut flushed_tail = false;
+        for (index, element) in payload_elements.iter().enumerate() {
+            let bits = element_bits(element)?;
+            let terminator = bits
+                .iter()
+                .rposition(|bit| *bit)
+                .filter(|terminator| *terminator <= RecordEncoder::PAYLOAD_ELEMENT_BITSIZE)
+                .ok_or(DPCError::CorruptReservedBit { element_index: 5 + index })?;
+            if terminator != RecordEncoder::PAYLOAD_ELEMENT_BITSIZE {
+                if index + 1 != payload_elements.len() {
+                    return Err(DPCError::CorruptReservedBit { element_index: 5 + index });
+                }
+                flushed_tail = true;
+            }
+            payload_bits.extend_from_slice(&bits[..terminator]);
+        }
+
+        let final_bits = element_bits(&elements[elements.len() - 1])?;
+        let value: u64 = FromBytes::read(&bits_to_bytes(&final_bits[..RecordEncoder::VALUE_BITSIZE])[..])?;
+        let tail_bits = &final_bits[RecordEncoder::VALUE_BITSIZE..];
+        let terminator = tail_bits
+            .iter()
+            .rposition(|bit| *bit)
+            .ok_or(DPCError::CorruptReservedBit { element_index: elements.len() - 1 })?;
+        // A flushed tail leaves the final element with no payload bits of its own.
+        if flushed_tail && terminator != 0 {
+            return Err(DPCError::CorruptReservedBit { element_index: elements.len() - 1 });
+        }
+        payload_bits.extend_from_slice(&tail_bits[..terminator]);
+
+        // The element count must be exactly what this payload flattens into, the flush
+        // element included.
+        let payload_tail_bits = payload_bits.len() % RecordEncoder::PAYLOAD_ELEMENT_BITSIZE;
+        let needs_flush =
+            RecordEncoder::VALUE_BITSIZE + payload_tail_bits + 1 > RecordEncoder::DATA_ELEMENT_BITSIZE;
+        let expected_len =
+            6 + payload_bits.len() / RecordEncoder::PAYLOAD_ELEMENT_BITSIZE + needs_flush as usize;
+        if elements.len() != expected_len || payload_bits.len() % 8 != 0 {
+            return Err(DPCError::EncodingInvariant {
+                expected: expected_len,
+                got: elements.len(),
+            });
+        }
+
+        Ok(DecodedRecord {
+            value,
+            payload: Payload::from_bytes(&bits_to_bytes(&payload_bits)),
+            birth_program_id,
+            death_program_id,
+            serial_number_nonce,
+            commitment_randomness,
+            owner: None,
+        })
+    }
+}
+
+impl std::fmt::Display for DecodedRecord {
+    /// Renders a one-line summary of the record for operator-facing logs, with the
+    /// program ids abbreviated to their first and last two bytes.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "DecodedRecord {{ value: {}, payload_len: {}, birth: {}, death: {} }}",
+            self.value,
+            self.payload.len(),
+            abbreviate_hex(&self.birth_program_id),
+            abbreviate_hex(&self.death_program_id)
+        )
+    }
+}
+
+/// Parses a `0x`-prefixed hex string, returning `None` on malformed input.
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    let digits = hex.strip_prefix("0x")?;
+    if digits.len() % 2 != 0 {
+        return None;
+    }
+    digits
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect()
+}
+
+/// Renders bytes as a `0x`-prefixed lowercase hex string.
+fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(2 + 2 * bytes.len());
+    hex.push_str("0x");
+    for byte in bytes {
+        hex.push_str(&format!("{:02x}", byte));
+    }
+    hex
+}
+
+/// Abbreviates bytes to their first and last two bytes in hex, e.g. `0xab01..cd02`.
+fn abbreviate_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(2 + 2 * bytes.len().min(5));
+    hex.push_str("0x");
+    if bytes.len() <= 4 {
+        for byte in bytes {
+            hex.push_str(&format!("{:02x}", byte));
+        }
+    } else {
+        hex.push_str(&format!(
+            "{:02x}{:02x}..{:02x}{:02x}",
+            bytes[0],
+            bytes[1],
+            bytes[bytes.len() - 2],
+            bytes[bytes.len() - 1]
+        ));
+    }
+    hex
+}
+
+impl From<&Record> for DecodedRecord {
+    /// Builds the decoded view by cloning only the fields it holds, leaving the record
+    /// (and in particular its owner and commitment) untouched for further use.
+    fn from(record: &Record) -> Self {
+        Self {
+            value: record.value,
+            payload: record.payload.clone(),
+            birth_program_id: record.birth_program_id.clone(),
+            death_program_id: record.death_program_id.clone(),
+            serial_number_nonce: record.serial_number_nonce,
+            commitment_randomness: record.commitment_randomness,
+            owner: None,
+        }
+    }
+}
+
+impl From<Record> for DecodedRecord {
+    fn from(record: Record) -> Self {
+        Self {
+            value: record.value,
+            payload: record.payload,
+            birth_program_id: record.birth_program_id,
+            death_program_id: record.death_program_id,
+            serial_number_nonce: record.serial_number_nonce,
+            commitment_randomness: record.commitment_randomness,
+            owner: None,
+        }
+    }
+}
@@ -0,0 +1,1856 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+    columnar::ColumnarRecords,
+    decoded::{DecodedRecord, PartialDecodedRecord},
+    errors::{DPCError, RecordError},
+    merkle::{MerklePath, MerkleRoot},
+    payload::Payload,
+    record::{CommitmentRandomness, InnerField, OuterField, Record, RecordInterface, SerialNumberNonce},
+};
+
+use snarkvm_algorithms::{encoding::Elligator2, prf::Blake2s, traits::PRF};
+use snarkvm_curves::{
+    edwards_bls12::{EdwardsAffine, EdwardsParameters, EdwardsProjective},
+    traits::{AffineCurve, ModelParameters, ProjectiveCurve},
+};
+use snarkvm_fields::{FieldParameters, PrimeField, Zero};
+use snarkvm_utilities::{bits_to_bytes, bytes_to_bits, to_bytes, BigInteger, FromBytes, ToBytes, UniformRand};
+
+/// Emits a `tracing::trace!` event when the `tracing` feature is enabled, and compiles
+/// to nothing when it is off, so the encode and decode paths can log each stage without
+/// a runtime cost in the default build.
+#[cfg(feature = "tracing")]
+macro_rules! trace_encoding {
+    ($($arg:tt)*) => { tracing::trace!($($arg)*) };
+}
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_encoding {
+    ($($arg:tt)*) => {};
+}
+
+/// The twisted Edwards parameters of the encoding group.
+pub type Parameters = EdwardsParameters;
+
+/// The group that record elements are encoded into.
+pub type Group = EdwardsProjective;
+
+/// The affine representation of the encoding group.
+pub type Affine = EdwardsAffine;
+
+/// The scalar field of the encoding group.
+pub type ScalarField = <EdwardsParameters as ModelParameters>::ScalarField;
+
+/// The per-element sign bits packed into a serialized record's final element, with each
+/// bit mapped to the record field its element encodes.
+///
+/// This is the named view of the positional `fq_high_bits` slice that `deserialize`
+/// consumes, for auditing the sign metadata of a serialization.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HighBits {
+    pub serial_number_nonce: bool,
+    pub commitment_randomness: bool,
+    pub birth_program_id: bool,
+    pub death_program_id: bool,
+    pub program_id_remainder: bool,
+    /// One bit per payload-carrying element, in element order.
+    pub payload: Vec<bool>,
+}
+
+/// Encodes a record into a vector of group elements, and decodes it back.
+///
+/// The serialized form is a vector of group elements together with one sign bit
+/// (`final_sign_high`) for the final element. `final_sign_high` is exactly the
+/// disambiguation bit `encode_to_group` (Elligator2) returns for the final element: the
+/// encode maps a field preimage and its negation to the same group element, and the bit
+/// records which of the two the data bits produced, so `decode_from_group` can pick the
+/// right one. It is not free to normalize — decoding with the flipped bit recovers the
+/// negated preimage and corrupts the final element's bits. The bit equals the last
+/// entry of the per-element sign bits that `serialize_with_high_bits` returns, which is
+/// how it can be cross-checked against an external implementation. The element layout
+/// is:
+///
+/// - `[0]` the serial number nonce, recovered directly from its bytes;
+/// - `[1]` the commitment randomness;
+/// - `[2]` the low bits of the birth program id;
+/// - `[3]` the low bits of the death program id;
+/// - `[4]` the remainder bits of both program ids, birth first;
+/// - `[5..]` the payload, `PAYLOAD_ELEMENT_BITSIZE` bits per element;
+/// - the final element packs a reserved bit, the per-element sign bits, the value bits,
+///   and any payload tail bits followed by a terminator bit.
+///
+/// Every payload-carrying element is delimited by a single `1` bit after its payload
+/// bits, so the exact payload bit count is recoverable on decode. The encoding is fully
+/// deterministic for a fixed input record.
+///
+/// All bit sequences are little-endian: `bytes_to_bits` emits the least significant bit
+/// of each byte first, and `BigInteger::get_bit(i)` reads bit `i` counted from the least
+/// significant end. A program id's bits `[0..DATA_ELEMENT_BITSIZE)` therefore land in
+/// its own element, and its high bits land in the shared remainder element, birth id
+/// first. This ordering is frozen by `test_program_id_bit_ordering`.
+pub struct RecordEncoder;
+
+impl RecordEncoder {
+    pub const DATA_ELEMENT_BITSIZE: usize = Self::INNER_FIELD_BITSIZE - 1;
+    pub const INNER_FIELD_BITSIZE: usize = <<InnerField as PrimeField>::Parameters as FieldParameters>::MODULUS_BITS as usize;
+    pub const OUTER_FIELD_BITSIZE: usize = <<OuterField as PrimeField>::Parameters as FieldParameters>::MODULUS_BITS as usize;
+    pub const PAYLOAD_ELEMENT_BITSIZE: usize = Self::DATA_ELEMENT_BITSIZE - 1;
+    /// The version byte prepended by `serialize_versioned`.
+    pub const RECORD_FORMAT_VERSION: u8 = 1;
+    pub const SCALAR_FIELD_BITSIZE: usize = <<ScalarField as PrimeField>::Parameters as FieldParameters>::MODULUS_BITS as usize;
+    /// The bit width of the record value, derived from the record's associated `Value`
+    /// type so that `serialize` and `deserialize` always agree on it.
+    ///
+    /// The value is packed little-endian at both levels: the least significant byte
+    /// first, and the least significant bit of each byte first, matching
+    /// `value.to_le_bytes()` passed through `bytes_to_bits`. This is pinned by
+    /// `test_value_endianness`.
+    pub const VALUE_BITSIZE: usize = std::mem::size_of::<<Record as RecordInterface>::Value>() * 8;
+
+    /// Encodes the given record into group elements, returning the elements and the
+    /// sign bit of the final element.
+    ///
+    /// The encoding is fully deterministic: every step, including the group element
+    /// recovered from the serial number nonce bytes via `from_random_bytes`, is a pure
+    /// function of the record's fields, so serializing the same record twice yields
+    /// identical output.
+    pub fn serialize(record: &Record) -> Result<(Vec<Group>, bool), DPCError> {
+        let (serialized_record, final_sign_high, _) = Self::serialize_with_high_bits(record)?;
+        Ok((serialized_record, final_sign_high))
+    }
+
+    /// Encodes the given record like `serialize`, additionally returning the sign bit of
+    /// every group element for cross-checking against external implementations.
+    ///
+    /// The returned bits align positionally with the returned group elements; the last
+    /// entry duplicates `final_sign_high`.
+    pub fn serialize_with_high_bits(record: &Record) -> Result<(Vec<Group>, bool, Vec<bool>), DPCError> {
+        Self::serialize_parts(record)
+    }
+
+    /// Encodes any record implementing `RecordInterface`, so wrapper record types can be
+    /// serialized without first converting to the crate's `Record`.
+    ///
+    /// The value bit width follows the implementor's `Value` type; `deserialize` assumes
+    /// the width of `Record`'s value, so records with a different width need a matching
+    /// decoder.
+    pub fn serialize_interface<R: RecordInterface>(record: &R) -> Result<(Vec<Group>, bool), DPCError> {
+        let (serialized_record, final_sign_high, _) = Self::serialize_parts(record)?;
+        Ok((serialized_record, final_sign_high))
+    }
+
+    /// The encode body shared by `serialize`, `serialize_with_high_bits`, and
+    /// `serialize_interface`, generic over the record's accessors.
+    fn serialize_parts<R: RecordInterface>(record: &R) -> Result<(Vec<Group>, bool, Vec<bool>), DPCError> {
+        let (data_elements, final_sign_high, data_high_bits) = Self::serialize_affine_parts(record)?;
+
+        // Compute the output group elements.
+        let mut output = Vec::with_capacity(data_elements.len());
+        for element in data_elements.iter() {
+            output.push(element.into_projective());
+        }
+
+        Ok((output, final_sign_high, data_high_bits))
+    }
+
+    /// Encodes the given record like `serialize`, returning the elements in affine form
+    /// directly, for consumers that store coordinates and would otherwise pay an
+    /// `into_projective`/`into_affine` round-trip.
+    pub fn serialize_affine(record: &Record) -> Result<(Vec<Affine>, bool), DPCError> {
+        let (data_elements, final_sign_high, _) = Self::serialize_affine_parts(record)?;
+        Ok((data_elements, final_sign_high))
+    }
+
+    /// The encode body behind `serialize_parts` and `serialize_affine`, producing the
+    /// elements in the affine form they are built in.
+    fn serialize_affine_parts<R: RecordInterface>(record: &R) -> Result<(Vec<Affine>, bool, Vec<bool>), DPCError> {
+        let (mut data_elements, mut data_high_bits) = Self::encode_base_elements(record)?;
+
+        // Process the payload.
+        let payload_bytes = record.payload().to_bytes();
+        if payload_bytes.len() > Payload::CAPACITY {
+            return Err(DPCError::PayloadTooLarge(payload_bytes.len(), Payload::CAPACITY));
+        }
+        let payload_bits = bytes_to_bits(&payload_bytes);
+        let payload_bits_count = payload_bits.len();
+
+        let mut payload_field_bits = Vec::with_capacity(Self::PAYLOAD_ELEMENT_BITSIZE + 1);
+
+        for bit in payload_bits.iter() {
+            payload_field_bits.push(*bit);
+
+            if payload_field_bits.len() == Self::PAYLOAD_ELEMENT_BITSIZE {
+                push_payload_element(&mut payload_field_bits, &mut data_elements, &mut data_high_bits)?;
+            }
+        }
+
+        let num_payload_elements = payload_bits_count / Self::PAYLOAD_ELEMENT_BITSIZE;
+        ensure_element_count(&data_elements, &data_high_bits, 5 + num_payload_elements)?;
+
+        // Any bits left in the buffer form a partial element: `encode_final_element`
+        // either packs them into the final element or flushes them into one extra
+        // element, so the tail is handled for arbitrary payload bit counts, not just
+        // multiples of `PAYLOAD_ELEMENT_BITSIZE` or of 8.
+        let value_bits = bytes_to_bits(&to_bytes![record.value()]?);
+        let final_sign_high =
+            Self::encode_final_element(&value_bits, payload_field_bits, &mut data_elements, &mut data_high_bits)?;
+
+        let expected_len = Self::element_count_for_value_bits(payload_bytes.len(), value_bits.len());
+        if data_elements.len() != expected_len {
+            return Err(DPCError::EncodingInvariant {
+                expected: expected_len,
+                got: data_elements.len(),
+            });
+        }
+
+        Ok((data_elements, final_sign_high, data_high_bits))
+    }
+
+    /// Encodes the given record like `serialize`, pulling the payload bytes from an
+    /// iterator instead of the record's own payload, which is ignored.
+    ///
+    /// Each `PAYLOAD_ELEMENT_BITSIZE` chunk is encoded as it fills, so the payload is
+    /// never materialized in memory; this suits payloads produced by a streaming
+    /// transform. The element accounting matches `serialize` on the same payload bytes,
+    /// and the capacity bound is enforced as the iterator is drained.
+    pub fn serialize_streaming_payload<I: Iterator<Item = u8>>(
+        record: &Record,
+        payload: I,
+    ) -> Result<(Vec<Group>, bool), DPCError> {
+        let (mut data_elements, mut data_high_bits) = Self::encode_base_elements(record)?;
+
+        let mut payload_len = 0usize;
+        let mut payload_field_bits = Vec::with_capacity(Self::PAYLOAD_ELEMENT_BITSIZE + 1);
+
+        for byte in payload {
+            payload_len += 1;
+            if payload_len > Payload::CAPACITY {
+                return Err(DPCError::PayloadTooLarge(payload_len, Payload::CAPACITY));
+            }
+
+            // The bit order matches `bytes_to_bits`: least significant bit first.
+            for shift in 0..8 {
+                payload_field_bits.push((byte >> shift) & 1 == 1);
+
+                if payload_field_bits.len() == Self::PAYLOAD_ELEMENT_BITSIZE {
+                    push_payload_element(&mut payload_field_bits, &mut data_elements, &mut data_high_bits)?;
+                }
+            }
+        }
+
+        let num_payload_elements = (payload_len * 8) / Self::PAYLOAD_ELEMENT_BITSIZE;
+        ensure_element_count(&data_elements, &data_high_bits, 5 + num_payload_elements)?;
+
+        let value_bits = bytes_to_bits(&to_bytes![record.value()]?);
+        let final_sign_high =
+            Self::encode_final_element(&value_bits, payload_field_bits, &mut data_elements, &mut data_high_bits)?;
+
+        let expected_len = Self::element_count_for_value_bits(payload_len, value_bits.len());
+        if data_elements.len() != expected_len {
+            return Err(DPCError::EncodingInvariant {
+                expected: expected_len,
+                got: data_elements.len(),
+            });
+        }
+
+        let mut output = Vec::with_capacity(data_elements.len());
+        for element in data_elements.iter() {
+            output.push(element.into_projective());
+        }
+
+        Ok((output, final_sign_high))
+    }
+
+    /// Re-encodes a record with a modified payload and value, reusing the five fixed
+    /// leading elements of the original serialization instead of recomputing them.
+    ///
+    /// The original final sign bit is required to recover the fixed elements' sign bits
+    /// from the original final element, since they are not derivable from the group
+    /// elements alone. Only the payload elements and the final element are recomputed,
+    /// which is a meaningful speedup when only payloads change.
+    pub fn reserialize_payload_only(
+        original_serialized: &[Group],
+        original_final_sign_high: bool,
+        modified_payload: &Payload,
+        value: u64,
+    ) -> Result<(Vec<Group>, bool), DPCError> {
+        if original_serialized.len() < 6 {
+            return Err(RecordError::ShortSerialization(original_serialized.len()).into());
+        }
+
+        // Recover the sign bits of the fixed elements from the original final element.
+        let original_final_element = &original_serialized[original_serialized.len() - 1];
+        let original_final_element_bytes =
+            decode_from_group(original_final_element.into_affine(), original_final_sign_high)?;
+        let original_final_element_bits = bytes_to_bits(&original_final_element_bytes);
+        let fq_high_bits = extract_fq_high_bits(&original_final_element_bits, original_serialized.len())?;
+
+        let mut data_elements: Vec<Affine> =
+            original_serialized[..5].iter().map(|element| element.into_affine()).collect();
+        let mut data_high_bits = fq_high_bits[..5].to_vec();
+
+        // Re-encode the payload elements.
+        let payload_bytes = modified_payload.to_bytes();
+        if payload_bytes.len() > Payload::CAPACITY {
+            return Err(DPCError::PayloadTooLarge(payload_bytes.len(), Payload::CAPACITY));
+        }
+        let mut payload_field_bits = Vec::with_capacity(Self::PAYLOAD_ELEMENT_BITSIZE + 1);
+        for bit in bytes_to_bits(&payload_bytes) {
+            payload_field_bits.push(bit);
+            if payload_field_bits.len() == Self::PAYLOAD_ELEMENT_BITSIZE {
+                push_payload_element(&mut payload_field_bits, &mut data_elements, &mut data_high_bits)?;
+            }
+        }
+
+        let value_bits = bytes_to_bits(&to_bytes![value]?);
+        let final_sign_high =
+            Self::encode_final_element(&value_bits, payload_field_bits, &mut data_elements, &mut data_high_bits)?;
+
+        let expected_len = Self::element_count_for_value_bits(payload_bytes.len(), value_bits.len());
+        if data_elements.len() != expected_len {
+            return Err(DPCError::EncodingInvariant {
+                expected: expected_len,
+                got: data_elements.len(),
+            });
+        }
+
+        let mut output = Vec::with_capacity(data_elements.len());
+        for element in data_elements.iter() {
+            output.push(element.into_projective());
+        }
+
+        Ok((output, final_sign_high))
+    }
+
+    /// Encodes the five fixed leading elements of a record: the serial number nonce, the
+    /// commitment randomness, the two program id low halves, and the shared remainder.
+    fn encode_base_elements<R: RecordInterface>(record: &R) -> Result<(Vec<Affine>, Vec<bool>), DPCError> {
+        // Assumption 1 - The scalar field bit size must be strictly less than the base field bit size
+        // for the commitment randomness to encode into one element.
+        assert!(Self::SCALAR_FIELD_BITSIZE < Self::INNER_FIELD_BITSIZE);
+
+        // Assumption 2 - The outer field bit size exceeds the data element bit size by at most
+        // one data element bit size, so each program id fits into two elements.
+        assert!((Self::OUTER_FIELD_BITSIZE - Self::DATA_ELEMENT_BITSIZE) <= Self::DATA_ELEMENT_BITSIZE);
+
+        // Assumption 3 - The remainders of two outer field elements fit into one data element.
+        assert!((2 * (Self::OUTER_FIELD_BITSIZE - Self::DATA_ELEMENT_BITSIZE)) <= Self::DATA_ELEMENT_BITSIZE);
+
+        let mut data_elements = vec![];
+        let mut data_high_bits = vec![];
+
+        // Process the serial number nonce. This element is already in the constraint field,
+        // so a group element is recovered directly from its bytes.
+        let serial_number_nonce_bytes = to_bytes![record.serial_number_nonce()]?;
+        let serial_number_nonce_encoded = Affine::from_random_bytes(&serial_number_nonce_bytes)
+            .ok_or_else(|| DPCError::Message("the serial number nonce does not encode into the group".to_string()))?;
+        data_elements.push(serial_number_nonce_encoded);
+        data_high_bits.push(false);
+        trace_encoding!(element = 0, sign_high = false, "encoded serial number nonce");
+
+        ensure_element_count(&data_elements, &data_high_bits, 1)?;
+
+        // Process the commitment randomness. (Assumption 1 applies)
+        let (encoded_commitment_randomness, sign_high) = encode_to_group(&to_bytes![record.commitment_randomness()]?)?;
+        data_elements.push(encoded_commitment_randomness);
+        data_high_bits.push(sign_high);
+        trace_encoding!(element = 1, sign_high, "encoded commitment randomness");
+
+        ensure_element_count(&data_elements, &data_high_bits, 2)?;
+
+        // Process the birth and death program ids. (Assumptions 2 and 3 apply)
+        let birth_program_id_biginteger = read_program_id(record.birth_program_id())?.into_repr();
+        let death_program_id_biginteger = read_program_id(record.death_program_id())?.into_repr();
+
+        let mut birth_program_id_bits = Vec::with_capacity(Self::DATA_ELEMENT_BITSIZE);
+        let mut death_program_id_bits = Vec::with_capacity(Self::DATA_ELEMENT_BITSIZE);
+        let mut birth_program_id_remainder_bits =
+            Vec::with_capacity(Self::OUTER_FIELD_BITSIZE - Self::DATA_ELEMENT_BITSIZE);
+        let mut death_program_id_remainder_bits =
+            Vec::with_capacity(Self::OUTER_FIELD_BITSIZE - Self::DATA_ELEMENT_BITSIZE);
+
+        for i in 0..Self::OUTER_FIELD_BITSIZE {
+            if i < Self::DATA_ELEMENT_BITSIZE {
+                birth_program_id_bits.push(birth_program_id_biginteger.get_bit(i));
+                death_program_id_bits.push(death_program_id_biginteger.get_bit(i));
+            } else {
+                birth_program_id_remainder_bits.push(birth_program_id_biginteger.get_bit(i));
+                death_program_id_remainder_bits.push(death_program_id_biginteger.get_bit(i));
+            }
+        }
+
+        // (Assumption 3) The remainders are packed into one element, birth first.
+        let mut program_id_remainder_bits = birth_program_id_remainder_bits;
+        program_id_remainder_bits.append(&mut death_program_id_remainder_bits);
+
+        let (encoded_birth_program_id, sign_high) = encode_to_group(&bits_to_bytes(&birth_program_id_bits))?;
+        data_elements.push(encoded_birth_program_id);
+        data_high_bits.push(sign_high);
+        trace_encoding!(element = 2, sign_high, "encoded birth program id low bits");
+
+        let (encoded_death_program_id, sign_high) = encode_to_group(&bits_to_bytes(&death_program_id_bits))?;
+        data_elements.push(encoded_death_program_id);
+        data_high_bits.push(sign_high);
+        trace_encoding!(element = 3, sign_high, "encoded death program id low bits");
+
+        let (encoded_program_id_remainder, sign_high) = encode_to_group(&bits_to_bytes(&program_id_remainder_bits))?;
+        data_elements.push(encoded_program_id_remainder);
+        data_high_bits.push(sign_high);
+        trace_encoding!(element = 4, sign_high, "encoded program id remainders");
+
+        ensure_element_count(&data_elements, &data_high_bits, 5)?;
+
+        Ok((data_elements, data_high_bits))
+    }
+
+    /// Re-encodes a serialized record after overwriting the payload bytes in `range`
+    /// with `new_bytes`, recomputing only the payload elements that overlap the range
+    /// plus the final element, and reusing every other element as-is.
+    ///
+    /// `new_bytes` must match the range's length, so the payload length and the element
+    /// count never change. As with `reserialize_payload_only`, the original final sign
+    /// bit is required to recover the reused elements' sign bits from the original
+    /// final element. The final element is always rebuilt, since it packs the sign bit
+    /// ledger and the payload tail; when the `value_does_not_fit` flush element exists,
+    /// it is rebuilt with it.
+    pub fn update_payload_region(
+        serialized: &[Group],
+        original_final_sign_high: bool,
+        current_payload: &Payload,
+        range: std::ops::Range<usize>,
+        new_bytes: &[u8],
+    ) -> Result<(Vec<Group>, bool), DPCError> {
+        check_serialized_len(serialized)?;
+        if range.start > range.end || range.end > current_payload.len() {
+            return Err(DPCError::Message(format!(
+                "the region {}..{} falls outside the payload length of {} bytes",
+                range.start,
+                range.end,
+                current_payload.len()
+            )));
+        }
+        if new_bytes.len() != range.end - range.start {
+            return Err(DPCError::Message(format!(
+                "the region {}..{} cannot hold {} replacement bytes",
+                range.start,
+                range.end,
+                new_bytes.len()
+            )));
+        }
+        if serialized.len() != Self::element_count_for(current_payload.len()) {
+            return Err(DPCError::EncodingInvariant {
+                expected: Self::element_count_for(current_payload.len()),
+                got: serialized.len(),
+            });
+        }
+
+        let mut payload_bytes = current_payload.to_bytes();
+        payload_bytes[range.clone()].copy_from_slice(new_bytes);
+
+        // The value and the original sign bit ledger both come from the final element.
+        let value = Self::decode_value_only(serialized, original_final_sign_high)?;
+        let fq_high_bits = Self::decode_final_flags(serialized, original_final_sign_high)?;
+
+        let mut data_elements: Vec<Affine> = serialized[..5].iter().map(|element| element.into_affine()).collect();
+        let mut data_high_bits = fq_high_bits[..5].to_vec();
+
+        // Walk the payload in element-sized chunks, re-encoding a chunk only if its bit
+        // span overlaps the modified byte range.
+        let modified_bits = range.start * 8..range.end * 8;
+        let mut chunk_index = 0;
+        let mut payload_field_bits = Vec::with_capacity(Self::PAYLOAD_ELEMENT_BITSIZE + 1);
+        for bit in bytes_to_bits(&payload_bytes) {
+            payload_field_bits.push(bit);
+            if payload_field_bits.len() == Self::PAYLOAD_ELEMENT_BITSIZE {
+                let chunk_bits = chunk_index * Self::PAYLOAD_ELEMENT_BITSIZE..(chunk_index + 1) * Self::PAYLOAD_ELEMENT_BITSIZE;
+                if chunk_bits.start < modified_bits.end && modified_bits.start < chunk_bits.end {
+                    push_payload_element(&mut payload_field_bits, &mut data_elements, &mut data_high_bits)?;
+                } else {
+                    data_elements.push(serialized[5 + chunk_index].into_affine());
+                    data_high_bits.push(fq_high_bits[5 + chunk_index]);
+                    payload_field_bits.clear();
+                }
+                chunk_index += 1;
+            }
+        }
+
+        let value_bits = bytes_to_bits(&to_bytes![value]?);
+        let final_sign_high =
+            Self::encode_final_element(&value_bits, payload_field_bits, &mut data_elements, &mut data_high_bits)?;
+
+        if data_elements.len() != serialized.len() {
+            return Err(DPCError::EncodingInvariant {
+                expected: serialized.len(),
+                got: data_elements.len(),
+            });
+        }
+
+        let mut output = Vec::with_capacity(data_elements.len());
+        for element in data_elements.iter() {
+            output.push(element.into_projective());
+        }
+
+        Ok((output, final_sign_high))
+    }
+
+    /// Encodes the final element from the value bits and the buffered payload tail,
+    /// flushing the tail into one extra element first when the value does not fit.
+    /// Returns the final element's sign bit, which is also appended to `data_high_bits`.
+    fn encode_final_element(
+        value_bits: &[bool],
+        mut payload_field_bits: Vec<bool>,
+        data_elements: &mut Vec<Affine>,
+        data_high_bits: &mut Vec<bool>,
+    ) -> Result<bool, DPCError> {
+        // Determine whether the value bits fit into the final element, alongside the reserved
+        // bit, one sign bit per data element, the payload tail, and the payload terminator bit.
+        let value_does_not_fit =
+            (payload_field_bits.len() + data_high_bits.len() + value_bits.len() + 1) > Self::PAYLOAD_ELEMENT_BITSIZE;
+
+        if value_does_not_fit {
+            // (Assumption 4) Flush the payload tail into one extra element.
+            trace_encoding!("value does not fit; flushing the payload tail into an extra element");
+            push_payload_element(&mut payload_field_bits, data_elements, data_high_bits)?;
+
+            // The flush empties the payload tail but grows the sign bit ledger by one,
+            // so a wide enough value over a large enough payload can still overflow the
+            // final element. Reject it here rather than encode a corrupt element.
+            if data_high_bits.len() + value_bits.len() + 1 > Self::PAYLOAD_ELEMENT_BITSIZE {
+                return Err(RecordError::ValueDoesNotFit(value_bits.len(), data_high_bits.len()).into());
+            }
+        }
+
+        // Compose the final element from the reserved bit, the sign bits of all preceding
+        // elements, the value bits, and the terminated payload tail. The value bits begin at
+        // index `serialized_record.len()`, which decode relies on.
+        let mut final_element_bits = vec![true];
+        final_element_bits.extend_from_slice(data_high_bits);
+        final_element_bits.extend_from_slice(value_bits);
+        final_element_bits.append(&mut payload_field_bits);
+        final_element_bits.push(true);
+
+        let (encoded_final_element, final_sign_high) = encode_to_group(&bits_to_bytes(&final_element_bits))?;
+        data_elements.push(encoded_final_element);
+
+        // Record the final element's sign bit, so the high bits align with the output.
+        data_high_bits.push(final_sign_high);
+        trace_encoding!(
+            element = data_elements.len() - 1,
+            sign_high = final_sign_high,
+            value_bits = value_bits.len(),
+            "encoded final element"
+        );
+
+        Ok(final_sign_high)
+    }
+
+    /// Encodes the given record like `serialize`, re-verifying the encoding invariants
+    /// unconditionally on the serialized output.
+    ///
+    /// The element-count checks inside `serialize` already run in release builds, since
+    /// they are `Result`-based rather than debug assertions. This variant additionally
+    /// decodes the final element and confirms that the sign bit ledger it carries
+    /// matches the sign bits produced during the encode, so a bug that writes a
+    /// well-counted but inconsistent final element is caught before the output leaves
+    /// the process. Use `serialize` on performance-critical paths.
+    pub fn serialize_checked(record: &Record) -> Result<(Vec<Group>, bool), DPCError> {
+        let (serialized_record, final_sign_high, data_high_bits) = Self::serialize_with_high_bits(record)?;
+
+        let expected_len = Self::serialized_len(record);
+        if serialized_record.len() != expected_len {
+            return Err(DPCError::EncodingInvariant {
+                expected: expected_len,
+                got: serialized_record.len(),
+            });
+        }
+
+        let final_element = &serialized_record[serialized_record.len() - 1];
+        let final_element_bytes = decode_from_group(final_element.into_affine(), final_sign_high)?;
+        let final_element_bits = bytes_to_bits(&final_element_bytes);
+        let fq_high_bits = extract_fq_high_bits(&final_element_bits, serialized_record.len())?;
+        if fq_high_bits != &data_high_bits[..data_high_bits.len() - 1] {
+            return Err(DPCError::EncodingInvariant {
+                expected: data_high_bits.len() - 1,
+                got: fq_high_bits.iter().zip(&data_high_bits).filter(|(a, b)| a == b).count(),
+            });
+        }
+
+        Ok((serialized_record, final_sign_high))
+    }
+
+    /// Encodes the given record like `serialize`, returning the typed `SerializedRecord`
+    /// wrapper instead of the raw tuple.
+    pub fn serialize_into(record: &Record) -> Result<crate::serialized::SerializedRecord, DPCError> {
+        let (elements, final_sign_high) = Self::serialize(record)?;
+        Ok(crate::serialized::SerializedRecord { elements, final_sign_high })
+    }
+
+    /// Encodes the given record like `serialize`, prefixed with the record format
+    /// version for on-disk and wire use.
+    ///
+    /// The raw `serialize` output stays available for in-circuit use; long-term storage
+    /// should carry the version byte so a future format change cannot silently
+    /// mis-decode old records.
+    pub fn serialize_versioned(record: &Record) -> Result<(u8, Vec<Group>, bool), DPCError> {
+        let (serialized_record, final_sign_high) = Self::serialize(record)?;
+        Ok((Self::RECORD_FORMAT_VERSION, serialized_record, final_sign_high))
+    }
+
+    /// Decodes a serialized record after checking its format version byte.
+    pub fn deserialize_versioned(
+        version: u8,
+        serialized_record: &[Group],
+        final_sign_high: bool,
+    ) -> Result<DecodedRecord, DPCError> {
+        if version != Self::RECORD_FORMAT_VERSION {
+            return Err(DPCError::UnsupportedVersion {
+                got: version,
+                supported: Self::RECORD_FORMAT_VERSION,
+            });
+        }
+        Self::deserialize(serialized_record, final_sign_high)
+    }
+
+    /// Decodes a serialized record, given the sign bit of its final element.
+    pub fn deserialize(serialized_record: &[Group], final_sign_high: bool) -> Result<DecodedRecord, DPCError> {
+        let (decoded, _) = Self::deserialize_with_len(serialized_record, final_sign_high)?;
+        Ok(decoded)
+    }
+
+    /// Decodes a serialized record like `deserialize`, additionally returning the exact
+    /// number of payload bits that were encoded.
+    ///
+    /// The count equals the producer's `payload_bits_count` before byte packing, so a
+    /// consumer can distinguish encoded payload bits from the zero padding that
+    /// `bits_to_bytes` appends to fill the last byte.
+    pub fn deserialize_with_len(
+        serialized_record: &[Group],
+        final_sign_high: bool,
+    ) -> Result<(DecodedRecord, usize), DPCError> {
+        let (decoded, _, payload_bits_count) =
+            Self::deserialize_with_value_width(serialized_record, final_sign_high, Self::VALUE_BITSIZE)?;
+        Ok((decoded, payload_bits_count))
+    }
+
+    /// Decodes a record that was serialized with a 128-bit value type through
+    /// `serialize_interface`, returning the decoded record and the full value.
+    ///
+    /// The returned record's `value` field holds the low 64 bits of the wide value, so
+    /// existing call sites keep working; the `u128` alongside carries the full range.
+    pub fn deserialize_u128(
+        serialized_record: &[Group],
+        final_sign_high: bool,
+    ) -> Result<(DecodedRecord, u128), DPCError> {
+        let (decoded, value_bytes, _) =
+            Self::deserialize_with_value_width(serialized_record, final_sign_high, 128)?;
+
+        let mut wide_bytes = [0u8; 16];
+        wide_bytes.copy_from_slice(&value_bytes);
+        Ok((decoded, u128::from_le_bytes(wide_bytes)))
+    }
+
+    /// The decode body shared by `deserialize_with_len` and `deserialize_u128`,
+    /// parametrized over the value bit width, which fixes where the payload tail begins
+    /// in the final element. Returns the decoded record, the value's little-endian
+    /// bytes at full width, and the payload bit count.
+    fn deserialize_with_value_width(
+        serialized_record: &[Group],
+        final_sign_high: bool,
+        value_width: usize,
+    ) -> Result<(DecodedRecord, Vec<u8>, usize), DPCError> {
+        check_serialized_len(serialized_record)?;
+
+        // Decode the final element and recover the bit ledger.
+        let final_element = &serialized_record[serialized_record.len() - 1];
+        let final_element_bytes = decode_from_group(final_element.into_affine(), final_sign_high)?;
+        let final_element_bits = bytes_to_bits(&final_element_bytes);
+
+        // The sign bits of all preceding elements follow the reserved bit.
+        let fq_high_bits = extract_fq_high_bits(&final_element_bits, serialized_record.len())?;
+        trace_encoding!(
+            elements = serialized_record.len(),
+            final_sign_high,
+            "recovered the sign bit ledger from the final element"
+        );
+
+        // Deserialize the serial number nonce from its x-coordinate.
+        let (serial_number_nonce, _) = (&serialized_record[0], fq_high_bits[0]);
+        let serial_number_nonce_bytes = to_bytes![serial_number_nonce.into_affine().to_x_coordinate()]?;
+        let serial_number_nonce: SerialNumberNonce = FromBytes::read(&serial_number_nonce_bytes[..])?;
+        trace_encoding!(element = 0, "decoded serial number nonce");
+
+        // Deserialize the commitment randomness.
+        let (commitment_randomness_element, commitment_randomness_fq_high) =
+            (&serialized_record[1], fq_high_bits[1]);
+        let commitment_randomness_bytes =
+            decode_from_group(commitment_randomness_element.into_affine(), commitment_randomness_fq_high)?;
+        let commitment_randomness_bits = &bytes_to_bits(&commitment_randomness_bytes)[..Self::SCALAR_FIELD_BITSIZE];
+        let commitment_randomness: CommitmentRandomness =
+            FromBytes::read(&bits_to_bytes(commitment_randomness_bits)[..])?;
+        trace_encoding!(element = 1, sign_high = commitment_randomness_fq_high, "decoded commitment randomness");
+
+        // Deserialize the birth and death program ids.
+        let (birth_program_id_element, birth_program_id_fq_high) = (&serialized_record[2], fq_high_bits[2]);
+        let (death_program_id_element, death_program_id_fq_high) = (&serialized_record[3], fq_high_bits[3]);
+        let (program_id_remainder_element, program_id_remainder_fq_high) = (&serialized_record[4], fq_high_bits[4]);
+
+        let birth_program_id_bytes = decode_from_group(birth_program_id_element.into_affine(), birth_program_id_fq_high)?;
+        let death_program_id_bytes = decode_from_group(death_program_id_element.into_affine(), death_program_id_fq_high)?;
+        let program_id_remainder_bytes =
+            decode_from_group(program_id_remainder_element.into_affine(), program_id_remainder_fq_high)?;
+
+        let remainder_size = Self::OUTER_FIELD_BITSIZE - Self::DATA_ELEMENT_BITSIZE;
+        let program_id_remainder_bits = bytes_to_bits(&program_id_remainder_bytes);
+
+        let mut birth_program_id_bits = bytes_to_bits(&birth_program_id_bytes)[..Self::DATA_ELEMENT_BITSIZE].to_vec();
+        birth_program_id_bits.extend_from_slice(&program_id_remainder_bits[..remainder_size]);
+
+        let mut death_program_id_bits = bytes_to_bits(&death_program_id_bytes)[..Self::DATA_ELEMENT_BITSIZE].to_vec();
+        death_program_id_bits.extend_from_slice(&program_id_remainder_bits[remainder_size..2 * remainder_size]);
+
+        let birth_program_id = bits_to_bytes(&birth_program_id_bits);
+        let death_program_id = bits_to_bytes(&death_program_id_bits);
+        trace_encoding!(
+            birth_sign_high = birth_program_id_fq_high,
+            death_sign_high = death_program_id_fq_high,
+            remainder_sign_high = program_id_remainder_fq_high,
+            "decoded program ids"
+        );
+
+        // Deserialize the value. Its bits begin immediately after the sign bits.
+        let value_start = serialized_record.len();
+        let value_end = value_start + value_width;
+        if value_end > final_element_bits.len() {
+            return Err(RecordError::ValueBitsOutOfRange(value_end, final_element_bits.len()).into());
+        }
+        let value_bytes = bits_to_bytes(&final_element_bits[value_start..value_end]);
+
+        let mut low_bytes = [0u8; 8];
+        let low_len = value_bytes.len().min(8);
+        low_bytes[..low_len].copy_from_slice(&value_bytes[..low_len]);
+        let value = u64::from_le_bytes(low_bytes);
+
+        // Deserialize the payload elements, stripping each element's terminator bit. The
+        // sign bit count is tied to the element count, so a mismatch means corruption.
+        let payload_elements = &serialized_record[5..serialized_record.len() - 1];
+        if payload_elements.len() != fq_high_bits[5..].len() {
+            return Err(RecordError::FqHighBitsMismatch.into());
+        }
+        let mut payload_bits = vec![];
+        for (element, fq_high) in payload_elements.iter().zip(&fq_high_bits[5..]) {
+            let element_bytes = decode_from_group(element.into_affine(), *fq_high)?;
+            let element_bits = bytes_to_bits(&element_bytes);
+            let terminator = payload_terminator_position(&element_bits)?;
+            payload_bits.extend_from_slice(&element_bits[..terminator]);
+            trace_encoding!(sign_high = *fq_high, "decoded payload element");
+        }
+
+        // Recover the payload tail from the final element.
+        let tail_bits = &final_element_bits[value_end..];
+        let terminator = payload_terminator_position(tail_bits)?;
+        payload_bits.extend_from_slice(&tail_bits[..terminator]);
+
+        let payload_bits_count = payload_bits.len();
+        trace_encoding!(value, payload_bits = payload_bits_count, "decoded value and payload tail");
+        let payload = Payload::read(&mut &bits_to_bytes(&payload_bits)[..])?;
+
+        Ok((
+            DecodedRecord {
+                value,
+                payload,
+                birth_program_id,
+                death_program_id,
+                serial_number_nonce,
+                commitment_randomness,
+                owner: None,
+            },
+            value_bytes,
+            payload_bits_count,
+        ))
+    }
+
+    /// Encodes the given record like `serialize`, padding the output with identity group
+    /// elements to exactly `target_elements`, for circuits with a fixed witness layout.
+    ///
+    /// The identity never appears in an unpadded serialization, since every encoded
+    /// element carries at least its reserved or data bits, so `deserialize_padded` can
+    /// strip the padding unambiguously. Errors if the record alone already exceeds
+    /// `target_elements`.
+    pub fn serialize_padded(record: &Record, target_elements: usize) -> Result<(Vec<Group>, bool), DPCError> {
+        let (mut serialized_record, final_sign_high) = Self::serialize(record)?;
+        if serialized_record.len() > target_elements {
+            return Err(DPCError::EncodingInvariant {
+                expected: target_elements,
+                got: serialized_record.len(),
+            });
+        }
+        serialized_record.resize(target_elements, Group::zero());
+        Ok((serialized_record, final_sign_high))
+    }
+
+    /// Decodes a record serialized with `serialize_padded`, ignoring the trailing
+    /// identity padding.
+    pub fn deserialize_padded(serialized_record: &[Group], final_sign_high: bool) -> Result<DecodedRecord, DPCError> {
+        let unpadded_len = serialized_record
+            .iter()
+            .rposition(|element| !element.is_zero())
+            .map_or(0, |index| index + 1);
+        Self::deserialize(&serialized_record[..unpadded_len], final_sign_high)
+    }
+
+    /// Decodes a serialized record and rejects it if either program id is not in the
+    /// given allowlist.
+    ///
+    /// Fusing the decode and the policy check into one call keeps callers that consume
+    /// untrusted records from forgetting the check.
+    pub fn deserialize_with_allowed_programs(
+        serialized_record: &[Group],
+        final_sign_high: bool,
+        allowed: &std::collections::HashSet<Vec<u8>>,
+    ) -> Result<DecodedRecord, DPCError> {
+        let decoded = Self::deserialize(serialized_record, final_sign_high)?;
+
+        for program_id in [&decoded.birth_program_id, &decoded.death_program_id] {
+            if !allowed.contains(program_id) {
+                let mut hex = String::with_capacity(2 + 2 * program_id.len());
+                hex.push_str("0x");
+                for byte in program_id {
+                    hex.push_str(&format!("{:02x}", byte));
+                }
+                return Err(DPCError::DisallowedProgram(hex));
+            }
+        }
+
+        Ok(decoded)
+    }
+
+    /// Decodes each field of a serialized record independently, so one corrupted field
+    /// does not block inspection of the others.
+    ///
+    /// A failure in the final element poisons the fields recovered through its bit
+    /// ledger, which are reported with the underlying error message; the serial number
+    /// nonce decodes from its own element regardless.
+    pub fn deserialize_lenient(serialized_record: &[Group], final_sign_high: bool) -> PartialDecodedRecord {
+        if serialized_record.len() < 6 {
+            let short = || -> DPCError { RecordError::ShortSerialization(serialized_record.len()).into() };
+            return PartialDecodedRecord {
+                value: Err(short()),
+                payload: Err(short()),
+                birth_program_id: Err(short()),
+                death_program_id: Err(short()),
+                serial_number_nonce: Err(short()),
+                commitment_randomness: Err(short()),
+            };
+        }
+
+        // The serial number nonce does not depend on the final element.
+        let serial_number_nonce = (|| -> Result<SerialNumberNonce, DPCError> {
+            let bytes = to_bytes![serialized_record[0].into_affine().to_x_coordinate()]?;
+            Ok(FromBytes::read(&bytes[..])?)
+        })();
+
+        // Everything else is recovered through the final element's bit ledger.
+        let final_element_bits = (|| -> Result<Vec<bool>, DPCError> {
+            let final_element = &serialized_record[serialized_record.len() - 1];
+            let final_element_bytes = decode_from_group(final_element.into_affine(), final_sign_high)?;
+            let final_element_bits = bytes_to_bits(&final_element_bytes);
+            extract_fq_high_bits(&final_element_bits, serialized_record.len())?;
+            Ok(final_element_bits)
+        })();
+
+        let final_element_bits = match final_element_bits {
+            Ok(final_element_bits) => final_element_bits,
+            Err(error) => {
+                let message = error.to_string();
+                let poisoned = || DPCError::Message(message.clone());
+                return PartialDecodedRecord {
+                    value: Err(poisoned()),
+                    payload: Err(poisoned()),
+                    birth_program_id: Err(poisoned()),
+                    death_program_id: Err(poisoned()),
+                    serial_number_nonce,
+                    commitment_randomness: Err(poisoned()),
+                };
+            }
+        };
+        let fq_high_bits = &final_element_bits[1..serialized_record.len()];
+
+        let commitment_randomness = (|| -> Result<CommitmentRandomness, DPCError> {
+            let bytes = decode_from_group(serialized_record[1].into_affine(), fq_high_bits[1])?;
+            let bits = &bytes_to_bits(&bytes)[..Self::SCALAR_FIELD_BITSIZE];
+            Ok(FromBytes::read(&bits_to_bytes(bits)[..])?)
+        })();
+
+        let program_id_bits = |index: usize| -> Result<Vec<bool>, DPCError> {
+            let bytes = decode_from_group(serialized_record[index].into_affine(), fq_high_bits[index])?;
+            Ok(bytes_to_bits(&bytes))
+        };
+        let remainder_size = Self::OUTER_FIELD_BITSIZE - Self::DATA_ELEMENT_BITSIZE;
+        let program_id = |index: usize, remainder_range: std::ops::Range<usize>| -> Result<Vec<u8>, DPCError> {
+            let mut bits = program_id_bits(index)?[..Self::DATA_ELEMENT_BITSIZE].to_vec();
+            bits.extend_from_slice(&program_id_bits(4)?[remainder_range]);
+            Ok(bits_to_bytes(&bits))
+        };
+        let birth_program_id = program_id(2, 0..remainder_size);
+        let death_program_id = program_id(3, remainder_size..2 * remainder_size);
+
+        let value_start = serialized_record.len();
+        let value_end = value_start + Self::VALUE_BITSIZE;
+        let value = (|| -> Result<u64, DPCError> {
+            if value_end > final_element_bits.len() {
+                return Err(RecordError::ValueBitsOutOfRange(value_end, final_element_bits.len()).into());
+            }
+            Ok(FromBytes::read(&bits_to_bytes(&final_element_bits[value_start..value_end])[..])?)
+        })();
+
+        let payload = (|| -> Result<Payload, DPCError> {
+            let payload_elements = &serialized_record[5..serialized_record.len() - 1];
+            if payload_elements.len() != fq_high_bits[5..].len() {
+                return Err(RecordError::FqHighBitsMismatch.into());
+            }
+            let mut payload_bits = vec![];
+            for (element, fq_high) in payload_elements.iter().zip(&fq_high_bits[5..]) {
+                let element_bits = bytes_to_bits(&decode_from_group(element.into_affine(), *fq_high)?);
+                let terminator = payload_terminator_position(&element_bits)?;
+                payload_bits.extend_from_slice(&element_bits[..terminator]);
+            }
+            if value_end > final_element_bits.len() {
+                return Err(RecordError::ValueBitsOutOfRange(value_end, final_element_bits.len()).into());
+            }
+            let tail_bits = &final_element_bits[value_end..];
+            let terminator = payload_terminator_position(tail_bits)?;
+            payload_bits.extend_from_slice(&tail_bits[..terminator]);
+            Payload::read(&mut &bits_to_bytes(&payload_bits)[..])
+        })();
+
+        PartialDecodedRecord {
+            value,
+            payload,
+            birth_program_id,
+            death_program_id,
+            serial_number_nonce,
+            commitment_randomness,
+        }
+    }
+
+    /// Decodes a serialized record like `deserialize`, additionally verifying that every
+    /// reserved bit the encoding sets to `1` is actually set.
+    ///
+    /// This catches corrupted or maliciously crafted serializations early. The lenient
+    /// `deserialize` remains the default, since these checks decode the payload elements
+    /// a second time.
+    pub fn deserialize_strict(serialized_record: &[Group], final_sign_high: bool) -> Result<DecodedRecord, DPCError> {
+        check_serialized_len(serialized_record)?;
+
+        // The final element must carry its reserved leading bit.
+        let final_element = &serialized_record[serialized_record.len() - 1];
+        let final_element_bytes = decode_from_group(final_element.into_affine(), final_sign_high)?;
+        let final_element_bits = bytes_to_bits(&final_element_bytes);
+        if !final_element_bits[0] {
+            return Err(DPCError::CorruptReservedBit {
+                element_index: serialized_record.len() - 1,
+            });
+        }
+
+        let fq_high_bits = extract_fq_high_bits(&final_element_bits, serialized_record.len())?;
+
+        // Every payload element must carry its reserved terminator bit, and only the last
+        // one (the `value_does_not_fit` flush) may place it before the element boundary.
+        let payload_elements = &serialized_record[5..serialized_record.len() - 1];
+        if payload_elements.len() != fq_high_bits[5..].len() {
+            return Err(RecordError::FqHighBitsMismatch.into());
+        }
+        for (i, (element, fq_high)) in payload_elements.iter().zip(&fq_high_bits[5..]).enumerate() {
+            let element_bytes = decode_from_group(element.into_affine(), *fq_high)?;
+            let element_bits = bytes_to_bits(&element_bytes);
+            let terminator = payload_terminator_position(&element_bits)
+                .map_err(|_| DPCError::CorruptReservedBit { element_index: 5 + i })?;
+            if i + 1 < payload_elements.len() && terminator != Self::PAYLOAD_ELEMENT_BITSIZE {
+                return Err(DPCError::CorruptReservedBit { element_index: 5 + i });
+            }
+        }
+
+        Self::deserialize(serialized_record, final_sign_high)
+    }
+
+    /// Decodes only the payload of a serialized record, skipping the serial number nonce,
+    /// commitment randomness, and program id elements.
+    ///
+    /// This mirrors the payload portion of `deserialize` and is substantially cheaper when
+    /// the remaining fields are not needed.
+    pub fn decode_payload_only(serialized_record: &[Group], final_sign_high: bool) -> Result<Payload, DPCError> {
+        check_serialized_len(serialized_record)?;
+
+        // Decode the final element and recover the bit ledger.
+        let final_element = &serialized_record[serialized_record.len() - 1];
+        let final_element_bytes = decode_from_group(final_element.into_affine(), final_sign_high)?;
+        let final_element_bits = bytes_to_bits(&final_element_bytes);
+
+        let fq_high_bits = extract_fq_high_bits(&final_element_bits, serialized_record.len())?;
+
+        // The value bits are skipped over, but their position is needed to find the tail.
+        let value_start = serialized_record.len();
+        let value_end = value_start + Self::VALUE_BITSIZE;
+        if value_end > final_element_bits.len() {
+            return Err(RecordError::ValueBitsOutOfRange(value_end, final_element_bits.len()).into());
+        }
+
+        // Decode the payload elements, stripping each element's terminator bit.
+        let payload_elements = &serialized_record[5..serialized_record.len() - 1];
+        if payload_elements.len() != fq_high_bits[5..].len() {
+            return Err(RecordError::FqHighBitsMismatch.into());
+        }
+        let mut payload_bits = vec![];
+        for (element, fq_high) in payload_elements.iter().zip(&fq_high_bits[5..]) {
+            let element_bytes = decode_from_group(element.into_affine(), *fq_high)?;
+            let element_bits = bytes_to_bits(&element_bytes);
+            let terminator = payload_terminator_position(&element_bits)?;
+            payload_bits.extend_from_slice(&element_bits[..terminator]);
+        }
+
+        // Recover the payload tail from the final element.
+        let tail_bits = &final_element_bits[value_end..];
+        let terminator = payload_terminator_position(tail_bits)?;
+        payload_bits.extend_from_slice(&tail_bits[..terminator]);
+
+        Payload::read(&mut &bits_to_bytes(&payload_bits)[..])
+    }
+
+    /// Decodes the per-element sign bits from a serialized record's final element,
+    /// returning them as the named `HighBits` view.
+    pub fn decode_high_bits(serialized_record: &[Group], final_sign_high: bool) -> Result<HighBits, DPCError> {
+        check_serialized_len(serialized_record)?;
+
+        let final_element = &serialized_record[serialized_record.len() - 1];
+        let final_element_bytes = decode_from_group(final_element.into_affine(), final_sign_high)?;
+        let final_element_bits = bytes_to_bits(&final_element_bytes);
+
+        let fq_high_bits = extract_fq_high_bits(&final_element_bits, serialized_record.len())?;
+
+        Ok(HighBits {
+            serial_number_nonce: fq_high_bits[0],
+            commitment_randomness: fq_high_bits[1],
+            birth_program_id: fq_high_bits[2],
+            death_program_id: fq_high_bits[3],
+            program_id_remainder: fq_high_bits[4],
+            payload: fq_high_bits[5..].to_vec(),
+        })
+    }
+
+    /// Decodes the per-element sign bits from a serialized record's final element,
+    /// returning the raw `fq_high_bits` ledger as an owned vector, in element order.
+    ///
+    /// This is the positional counterpart to `decode_high_bits`: the bit at index `i`
+    /// belongs to serialized element `i`. Only the final element is decoded, so this is
+    /// a cheap pre-check compared to a full `deserialize`.
+    pub fn decode_final_flags(serialized_record: &[Group], final_sign_high: bool) -> Result<Vec<bool>, DPCError> {
+        check_serialized_len(serialized_record)?;
+
+        let final_element = &serialized_record[serialized_record.len() - 1];
+        let final_element_bytes = decode_from_group(final_element.into_affine(), final_sign_high)?;
+        let final_element_bits = bytes_to_bits(&final_element_bytes);
+
+        Ok(extract_fq_high_bits(&final_element_bits, serialized_record.len())?.to_vec())
+    }
+
+    /// Returns whether the record was serialized with the `value_does_not_fit` extra
+    /// payload element, decoding only the final element and one payload element.
+    ///
+    /// Regular payload elements are full, with their terminator at bit
+    /// `PAYLOAD_ELEMENT_BITSIZE`; the flush element holds a partial tail, so its
+    /// terminator sits earlier. The flush element is always last among the payload
+    /// elements, which makes the check local. A record with no payload elements never
+    /// carries the extra element.
+    pub fn decode_has_extra_value_element(serialized: &[Group], final_sign_high: bool) -> Result<bool, DPCError> {
+        check_serialized_len(serialized)?;
+        if serialized.len() == 6 {
+            return Ok(false);
+        }
+
+        let fq_high_bits = Self::decode_final_flags(serialized, final_sign_high)?;
+        let last_payload_element = &serialized[serialized.len() - 2];
+        let element_bytes = decode_from_group(last_payload_element.into_affine(), fq_high_bits[serialized.len() - 2])?;
+        let terminator = payload_terminator_position(&bytes_to_bits(&element_bytes))?;
+
+        Ok(terminator != Self::PAYLOAD_ELEMENT_BITSIZE)
+    }
+
+    /// Decodes only the value of a serialized record, touching just the final element.
+    ///
+    /// The value bits always begin at index `serialized_record.len()` of the final
+    /// element's bits, so this holds whether or not the record was serialized with the
+    /// extra payload element from the `value_does_not_fit` case.
+    pub fn decode_value_only(serialized_record: &[Group], final_sign_high: bool) -> Result<u64, DPCError> {
+        check_serialized_len(serialized_record)?;
+
+        let final_element = &serialized_record[serialized_record.len() - 1];
+        let final_element_bytes = decode_from_group(final_element.into_affine(), final_sign_high)?;
+        let final_element_bits = bytes_to_bits(&final_element_bytes);
+
+        let value_start = serialized_record.len();
+        let value_end = value_start + Self::VALUE_BITSIZE;
+        if value_end > final_element_bits.len() {
+            return Err(RecordError::ValueBitsOutOfRange(value_end, final_element_bits.len()).into());
+        }
+        let value: u64 = FromBytes::read(&bits_to_bytes(&final_element_bits[value_start..value_end])[..])?;
+
+        Ok(value)
+    }
+
+    /// Decodes a record by reading exactly `element_count` group elements from the given
+    /// reader, for streams where elements arrive one at a time.
+    ///
+    /// The count can come from `serialized_len` on the producer side or from a length
+    /// prefix in the stream.
+    pub fn deserialize_from_reader<R: std::io::Read>(
+        reader: &mut R,
+        element_count: usize,
+        final_sign_high: bool,
+    ) -> Result<DecodedRecord, DPCError> {
+        let mut serialized_record = Vec::with_capacity(element_count);
+        for _ in 0..element_count {
+            serialized_record.push(Affine::read(&mut *reader)?.into_projective());
+        }
+        Self::deserialize(&serialized_record, final_sign_high)
+    }
+
+    /// Writes one record to the given writer in the self-delimiting frame format: a
+    /// little-endian `u32` element count, the uncompressed affine bytes of each element,
+    /// and the final sign bit as one byte.
+    ///
+    /// Frames written back to back form an appendable log that `read_framed` walks.
+    pub fn write_framed<W: std::io::Write>(writer: &mut W, record: &Record) -> Result<(), DPCError> {
+        let (serialized_record, final_sign_high) = Self::serialize(record)?;
+
+        writer.write_all(&(serialized_record.len() as u32).to_le_bytes())?;
+        for element in serialized_record.iter() {
+            writer.write_all(&to_bytes![element.into_affine()]?)?;
+        }
+        writer.write_all(&[final_sign_high as u8])?;
+        Ok(())
+    }
+
+    /// Reads one record frame written by `write_framed`, returning `Ok(None)` at a
+    /// clean end of stream and an error on a truncated or corrupt frame.
+    ///
+    /// The element count is bounds-checked against the largest frame an in-capacity
+    /// record can produce before any elements are read, so a corrupt length prefix
+    /// cannot trigger an oversized allocation.
+    pub fn read_framed<R: std::io::Read>(reader: &mut R) -> Result<Option<DecodedRecord>, DPCError> {
+        // A clean EOF before the length prefix marks the end of the stream; an EOF
+        // anywhere after it is a truncated frame.
+        let mut count_bytes = [0u8; 4];
+        if reader.read(&mut count_bytes[..1])? == 0 {
+            return Ok(None);
+        }
+        reader.read_exact(&mut count_bytes[1..])?;
+
+        let element_count = u32::from_le_bytes(count_bytes) as usize;
+        if element_count > crate::packed::MAX_ELEMENTS {
+            return Err(DPCError::EncodingInvariant {
+                expected: crate::packed::MAX_ELEMENTS,
+                got: element_count,
+            });
+        }
+
+        let mut serialized_record = Vec::with_capacity(element_count);
+        for _ in 0..element_count {
+            serialized_record.push(Affine::read(&mut *reader)?.into_projective());
+        }
+
+        let mut sign_byte = [0u8; 1];
+        reader.read_exact(&mut sign_byte)?;
+
+        Self::deserialize(&serialized_record, sign_byte[0] == 1).map(Some)
+    }
+
+    /// Decodes a single record from the start of the slice, returning the decoded record
+    /// and the number of group elements it consumed.
+    ///
+    /// The element count is not stored explicitly, so candidate lengths are tried in
+    /// ascending order until one decodes to a record whose payload implies exactly that
+    /// element count. Any trailing elements are left untouched, which allows metadata to
+    /// follow a record in the same stream.
+    pub fn deserialize_prefix(
+        serialized_record: &[Group],
+        final_sign_high: bool,
+    ) -> Result<(DecodedRecord, usize), DPCError> {
+        // The minimum record is the five fixed elements plus the final element.
+        for candidate_len in 6..=serialized_record.len() {
+            let decoded = match Self::deserialize(&serialized_record[..candidate_len], final_sign_high) {
+                Ok(decoded) => decoded,
+                Err(_) => continue,
+            };
+
+            // The element count implied by the decoded payload must match the candidate.
+            if Self::element_count_for(decoded.payload.len()) == candidate_len {
+                return Ok((decoded, candidate_len));
+            }
+        }
+
+        Err(DPCError::Message(
+            "no record was found at the start of the serialized stream".to_string(),
+        ))
+    }
+
+    /// Returns the number of `encode_to_group` curve operations `serialize` performs for
+    /// the given record: one per element except the serial number nonce, which is
+    /// recovered from its bytes instead of encoded.
+    pub fn encode_op_count(record: &Record) -> usize {
+        Self::serialized_len(record) - 1
+    }
+
+    /// Returns the `(start, end)` bit range the record's value occupies within its final
+    /// element's bits, matching the slice `deserialize` reads.
+    ///
+    /// The value bits follow the reserved bit and the per-element sign bits, so the
+    /// start is the serialized element count; the range therefore shifts with the
+    /// payload size. A circuit that selectively opens the value should slice exactly
+    /// these bits.
+    pub fn value_bit_range(record: &Record) -> (usize, usize) {
+        let value_start = Self::serialized_len(record);
+        (value_start, value_start + Self::VALUE_BITSIZE)
+    }
+
+    /// Iterates over the records in a flat stream of concatenated serialized records,
+    /// given one final-element sign bit per record.
+    ///
+    /// Each record's element count is recovered via `deserialize_prefix`, so the stream
+    /// needs no explicit framing. Malformed data yields one `Err` item, after which the
+    /// iterator stops, since the following record boundaries can no longer be trusted.
+    pub fn iter_records<'a>(
+        stream: &'a [Group],
+        sign_bits: &'a [bool],
+    ) -> impl Iterator<Item = Result<DecodedRecord, DPCError>> + 'a {
+        let mut offset = 0usize;
+        let mut record_index = 0usize;
+        let mut stopped = false;
+
+        std::iter::from_fn(move || {
+            if stopped || offset == stream.len() {
+                return None;
+            }
+            if record_index == sign_bits.len() {
+                stopped = true;
+                return Some(Err(RecordError::FqHighBitsMismatch.into()));
+            }
+
+            match Self::deserialize_prefix(&stream[offset..], sign_bits[record_index]) {
+                Ok((decoded, consumed)) => {
+                    offset += consumed;
+                    record_index += 1;
+                    Some(Ok(decoded))
+                }
+                Err(error) => {
+                    stopped = true;
+                    Some(Err(error))
+                }
+            }
+        })
+    }
+
+    /// Returns the number of group elements a record with the given payload byte length
+    /// occupies when serialized.
+    pub fn element_count_for(payload_len: usize) -> usize {
+        Self::element_count_for_value_bits(payload_len, Self::VALUE_BITSIZE)
+    }
+
+    /// Returns `true` if a serialization of `serialized_len` group elements is
+    /// consistent with a claimed payload of `payload_len` bytes under a value of
+    /// `value_width` bits.
+    ///
+    /// This is the inverse check of `serialized_len`: the element count is a pure
+    /// function of the payload length and value width, so a mismatch means one of the
+    /// two claims is wrong. It costs no decoding, making it a cheap integrity pre-check
+    /// before trusting either input. Pass `VALUE_BITSIZE` for records of the default
+    /// `u64` value type.
+    pub fn verify_element_count(serialized_len: usize, payload_len: usize, value_width: usize) -> bool {
+        Self::element_count_for_value_bits(payload_len, value_width) == serialized_len
+    }
+
+    /// Like `element_count_for`, parametrized over the value bit width, for records
+    /// serialized through `serialize_interface` with a non-default `Value` type.
+    fn element_count_for_value_bits(payload_len: usize, value_bits_count: usize) -> usize {
+        let num_payload_elements = (payload_len * 8) / Self::PAYLOAD_ELEMENT_BITSIZE;
+        5 + num_payload_elements + (Self::value_does_not_fit_for_bits(payload_len, value_bits_count) as usize) + 1
+    }
+
+    /// Returns `true` if serializing a record with the given record's payload triggers
+    /// the `value_does_not_fit` extra payload element.
+    ///
+    /// This is the same computation `serialize` performs, without running the encode, so
+    /// a size planner can bucket records ahead of time.
+    pub fn needs_extra_value_element(record: &Record) -> bool {
+        Self::value_does_not_fit_for_bits(record.payload().len(), Self::VALUE_BITSIZE)
+    }
+
+    /// Returns whether the final element of a record with the given payload byte length
+    /// cannot hold the payload tail alongside the reserved bit, the sign bits, the value
+    /// bits, and the payload terminator bit. This mirrors the `value_does_not_fit`
+    /// computation inside `serialize` bit-for-bit.
+    fn value_does_not_fit_for_bits(payload_len: usize, value_bits_count: usize) -> bool {
+        let payload_bits_count = payload_len * 8;
+        let num_payload_elements = payload_bits_count / Self::PAYLOAD_ELEMENT_BITSIZE;
+        let payload_tail_bits = payload_bits_count % Self::PAYLOAD_ELEMENT_BITSIZE;
+
+        let data_high_bits_count = 5 + num_payload_elements;
+        (payload_tail_bits + data_high_bits_count + value_bits_count + 1) > Self::PAYLOAD_ELEMENT_BITSIZE
+    }
+
+    /// Returns whether a record with the given payload byte length and value bit width
+    /// can be serialized at all.
+    ///
+    /// The `value_does_not_fit` flush empties the payload tail out of the final element
+    /// but grows the sign bit ledger by one, so after the flush the ledger, the value
+    /// bits, and the terminator must fit on their own. A `u64` value always fits within
+    /// the payload capacity; a 128-bit value stops fitting once the payload spans more
+    /// than 116 payload elements (roughly 3.6 KB). `serialize_interface` rejects such
+    /// records with `RecordError::ValueDoesNotFit`.
+    pub fn value_width_fits(payload_len: usize, value_bits_count: usize) -> bool {
+        if !Self::value_does_not_fit_for_bits(payload_len, value_bits_count) {
+            return true;
+        }
+        // Elements preceding the final one, including the flush element.
+        let data_high_bits_count = Self::element_count_for_value_bits(payload_len, value_bits_count) - 1;
+        data_high_bits_count + value_bits_count + 1 <= Self::PAYLOAD_ELEMENT_BITSIZE
+    }
+
+    /// Encodes the given record like `serialize`, but returns only the x-coordinate of
+    /// each element plus a per-element recovery bit, roughly halving the storage size.
+    ///
+    /// The recovery bit records whether the element's y-coordinate is the greater of the
+    /// two roots for that x. This also covers the serial number nonce element, which is
+    /// reconstructed from its coordinates like any other element.
+    pub fn serialize_compressed(record: &Record) -> Result<(Vec<InnerField>, Vec<bool>, bool), DPCError> {
+        let (serialized_record, final_sign_high) = Self::serialize(record)?;
+
+        let mut x_coordinates = Vec::with_capacity(serialized_record.len());
+        let mut greatest_bits = Vec::with_capacity(serialized_record.len());
+        for element in serialized_record.iter() {
+            let affine = element.into_affine();
+            let greatest = Affine::from_x_coordinate(affine.to_x_coordinate(), true)
+                .map(|candidate| candidate == affine)
+                .unwrap_or(false);
+            x_coordinates.push(affine.to_x_coordinate());
+            greatest_bits.push(greatest);
+        }
+
+        Ok((x_coordinates, greatest_bits, final_sign_high))
+    }
+
+    /// Decodes a record from its compressed form produced by `serialize_compressed`.
+    pub fn deserialize_compressed(
+        x_coordinates: &[InnerField],
+        greatest_bits: &[bool],
+        final_sign_high: bool,
+    ) -> Result<DecodedRecord, DPCError> {
+        if x_coordinates.len() != greatest_bits.len() {
+            return Err(RecordError::FqHighBitsMismatch.into());
+        }
+
+        let mut serialized_record = Vec::with_capacity(x_coordinates.len());
+        for (x_coordinate, greatest) in x_coordinates.iter().zip(greatest_bits) {
+            let element = Affine::from_x_coordinate(*x_coordinate, *greatest).ok_or_else(|| {
+                DPCError::Message("an x-coordinate of the compressed record does not lie on the curve".to_string())
+            })?;
+            serialized_record.push(element.into_projective());
+        }
+
+        Self::deserialize(&serialized_record, final_sign_high)
+    }
+
+    /// Serializes the record and renders one line per element, with the element's role,
+    /// its sign bit, and its affine bytes in hex, for debugging encoding mismatches
+    /// against another implementation.
+    ///
+    /// The labels follow the fixed element positions of `serialize`; payload elements
+    /// are labeled `payload[i]`.
+    pub fn dump(record: &Record) -> Result<String, DPCError> {
+        let (serialized_record, _, high_bits) = Self::serialize_with_high_bits(record)?;
+
+        let mut output = String::new();
+        for (i, (element, sign_high)) in serialized_record.iter().zip(&high_bits).enumerate() {
+            let label = match i {
+                0 => "serial_number_nonce".to_string(),
+                1 => "commitment_randomness".to_string(),
+                2 => "birth_program_id".to_string(),
+                3 => "death_program_id".to_string(),
+                4 => "program_id_remainder".to_string(),
+                i if i == serialized_record.len() - 1 => "final_element".to_string(),
+                i => format!("payload[{}]", i - 5),
+            };
+
+            let element_bytes = to_bytes![element.into_affine()]?;
+            let mut hex = String::with_capacity(2 * element_bytes.len());
+            for byte in element_bytes {
+                hex.push_str(&format!("{:02x}", byte));
+            }
+
+            output.push_str(&format!("[{:3}] {:<21} sign_high={:<5} 0x{}\n", i, label, sign_high, hex));
+        }
+
+        Ok(output)
+    }
+
+    /// Computes the crate-local record commitment for the given decoded record and
+    /// owner: BLAKE2s over `commitment_preimage`, keyed by the commitment randomness
+    /// bytes.
+    ///
+    /// This is not the DPC's `Components::RecordCommitment`. That scheme is a Pedersen
+    /// commitment whose parameters this crate does not carry, so the digests computed
+    /// here never equal commitments found on the ledger; checking a record against its
+    /// on-chain commitment requires `snarkvm-dpc`. The local commitment is binding in
+    /// the fields and hiding in the randomness, which suffices for cross-checking
+    /// records between parties that both compute it with this crate.
+    pub fn local_commitment_for(decoded: &DecodedRecord, owner: &[u8]) -> Result<[u8; 32], DPCError> {
+        let randomness_bytes = to_bytes![decoded.commitment_randomness]?;
+        let mut seed = [0u8; 32];
+        let seed_len = randomness_bytes.len().min(32);
+        seed[..seed_len].copy_from_slice(&randomness_bytes[..seed_len]);
+
+        blake2s_hash(&seed, &decoded.commitment_preimage(owner)?)
+    }
+
+    /// Decodes a serialized record and returns whether its recomputed crate-local
+    /// commitment equals `expected_commitment`. A mismatch is reported as `Ok(false)`;
+    /// errors are reserved for records that fail to decode.
+    ///
+    /// `expected_commitment` must itself be a `local_commitment_for` digest: a
+    /// commitment produced by the DPC's `Components::RecordCommitment` — anything taken
+    /// from the ledger — always compares unequal here.
+    pub fn verify_local_commitment(
+        serialized_record: &[Group],
+        final_sign_high: bool,
+        owner: &[u8],
+        expected_commitment: &[u8],
+    ) -> Result<bool, DPCError> {
+        let decoded = Self::deserialize(serialized_record, final_sign_high)?;
+        Ok(Self::local_commitment_for(&decoded, owner)?[..] == *expected_commitment)
+    }
+
+    /// Computes the crate-local commitments of both records and returns whether they
+    /// differ.
+    ///
+    /// Identical fields under identical owners commit identically, so a `false` result
+    /// on independently constructed records signals a construction bug upstream —
+    /// typically reused commitment randomness.
+    pub fn commitments_differ(
+        a: &Record,
+        a_owner: &[u8],
+        b: &Record,
+        b_owner: &[u8],
+    ) -> Result<bool, DPCError> {
+        let a_commitment = Self::local_commitment_for(&DecodedRecord::from(a), a_owner)?;
+        let b_commitment = Self::local_commitment_for(&DecodedRecord::from(b), b_owner)?;
+        Ok(a_commitment != b_commitment)
+    }
+
+    /// Builds a Merkle tree over the commitments of the given records, returning the
+    /// root and one inclusion proof per record, in input order.
+    ///
+    /// The leaves are `local_commitment_for` digests, each keyed by its record's commitment
+    /// randomness and bound to the owner at the same index, so `owners` must match
+    /// `records` in length. Verifying a path against the root with `MerklePath::verify`
+    /// proves a record's membership without revealing the other records.
+    pub fn commitment_tree(records: &[Record], owners: &[&[u8]]) -> Result<(MerkleRoot, Vec<MerklePath>), DPCError> {
+        if records.len() != owners.len() {
+            return Err(DPCError::Message(format!(
+                "cannot build a commitment tree over {} records with {} owners",
+                records.len(),
+                owners.len()
+            )));
+        }
+
+        let leaves = records
+            .iter()
+            .zip(owners)
+            .map(|(record, owner)| Self::local_commitment_for(&DecodedRecord::from(record), owner))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        crate::merkle::build_commitment_tree(&leaves)
+    }
+
+    /// Computes a stable 32-byte content hash of the record's serialized form, suitable
+    /// as a key in a content-addressed store.
+    ///
+    /// The hash is BLAKE2s over the concatenated affine bytes of every serialized
+    /// element followed by the final sign bit as one byte. Serialization is
+    /// deterministic, so equal records hash equally across runs.
+    pub fn content_hash(record: &Record) -> Result<[u8; 32], DPCError> {
+        let (serialized_record, final_sign_high) = Self::serialize(record)?;
+
+        let mut bytes = vec![];
+        for element in serialized_record.iter() {
+            bytes.extend_from_slice(&to_bytes![element.into_affine()]?);
+        }
+        bytes.push(final_sign_high as u8);
+
+        blake2s_hash(&[0u8; 32], &bytes)
+    }
+
+    /// Decodes a batch of serialized records across the rayon thread pool, returning
+    /// one result per record in input order.
+    ///
+    /// Each record decodes independently, so failures are reported per record rather
+    /// than aborting the batch, and the work scales with the available cores.
+    #[cfg(feature = "parallel")]
+    pub fn deserialize_batch_parallel(records: &[(Vec<Group>, bool)]) -> Vec<Result<DecodedRecord, DPCError>> {
+        use rayon::prelude::*;
+
+        records
+            .par_iter()
+            .map(|(serialized_record, final_sign_high)| Self::deserialize(serialized_record, *final_sign_high))
+            .collect()
+    }
+
+    /// Decodes a batch of serialized records into the column-wise `ColumnarRecords`
+    /// layout, in input order. The first decode failure aborts the batch.
+    pub fn deserialize_columnar(records: &[(Vec<Group>, bool)]) -> Result<ColumnarRecords, DPCError> {
+        let mut columnar = ColumnarRecords::default();
+        columnar.payload_offsets.push(0);
+
+        for (serialized_record, final_sign_high) in records {
+            let decoded = Self::deserialize(serialized_record, *final_sign_high)?;
+
+            columnar.values.push(decoded.value);
+            columnar.payload_bytes.extend_from_slice(decoded.payload.as_ref());
+            columnar.payload_offsets.push(columnar.payload_bytes.len());
+            columnar.birth_program_id_bytes.extend_from_slice(&decoded.birth_program_id);
+            columnar.death_program_id_bytes.extend_from_slice(&decoded.death_program_id);
+            columnar.serial_number_nonces.push(decoded.serial_number_nonce);
+            columnar.commitment_randomness.push(decoded.commitment_randomness);
+        }
+
+        Ok(columnar)
+    }
+
+    /// Returns the distinct birth and death program ids across the given records, as
+    /// two separate sets.
+    ///
+    /// The ids are compared as raw bytes, exactly as decoding produced them, so the
+    /// aggregation never re-interprets or normalizes them.
+    pub fn distinct_program_ids(
+        records: &[DecodedRecord],
+    ) -> (std::collections::HashSet<Vec<u8>>, std::collections::HashSet<Vec<u8>>) {
+        let mut birth_program_ids = std::collections::HashSet::new();
+        let mut death_program_ids = std::collections::HashSet::new();
+        for record in records {
+            birth_program_ids.insert(record.birth_program_id.clone());
+            death_program_ids.insert(record.death_program_id.clone());
+        }
+        (birth_program_ids, death_program_ids)
+    }
+
+    /// Returns every pair of indices `(i, j)` with `i < j` whose records share a serial
+    /// number nonce, compared by the nonce's canonical bytes.
+    ///
+    /// Two records with the same nonce derive the same serial number, so duplicates in
+    /// an incoming batch signal a double spend; an empty result clears the batch for
+    /// further validation.
+    pub fn find_duplicate_nonces(records: &[DecodedRecord]) -> Vec<(usize, usize)> {
+        let mut seen: std::collections::HashMap<Vec<u8>, Vec<usize>> = std::collections::HashMap::new();
+        let mut duplicates = vec![];
+
+        for (index, record) in records.iter().enumerate() {
+            // The nonce is a valid field element, so its bytes always serialize.
+            let nonce_bytes = to_bytes![record.serial_number_nonce].unwrap_or_default();
+            let indices = seen.entry(nonce_bytes).or_default();
+            for earlier in indices.iter() {
+                duplicates.push((*earlier, index));
+            }
+            indices.push(index);
+        }
+
+        duplicates
+    }
+
+    /// Returns `true` if two serialized records are equal, normalizing each group
+    /// element to affine form so differing projective representations compare equal.
+    /// The final sign bits are included in the comparison.
+    pub fn serialized_eq(a: &(Vec<Group>, bool), b: &(Vec<Group>, bool)) -> bool {
+        if a.1 != b.1 || a.0.len() != b.0.len() {
+            return false;
+        }
+        a.0.iter()
+            .zip(b.0.iter())
+            .all(|(left, right)| left.into_affine() == right.into_affine())
+    }
+
+    /// Generates `count` deterministic test vectors as a pretty-printed JSON string,
+    /// for cross-validating other implementations byte-for-byte.
+    ///
+    /// Vector `i` is built from `dummy_record_with_payload(seed + i, ...)` with a
+    /// payload of `i * 32` bytes drawn from the same seed, so consecutive vectors cover
+    /// the no-payload, single-element, and multi-element layouts. Each vector records
+    /// the input fields and the expected serialization: the hex of every element's
+    /// uncompressed affine bytes, and the final sign bit.
+    #[cfg(feature = "test-vectors")]
+    pub fn generate_test_vectors(count: usize, seed: u64) -> Result<String, DPCError> {
+        use rand::{RngCore, SeedableRng};
+
+        let hex = |bytes: &[u8]| -> String { bytes.iter().map(|byte| format!("{:02x}", byte)).collect() };
+
+        let mut vectors = Vec::with_capacity(count);
+        for i in 0..count {
+            let vector_seed = seed.wrapping_add(i as u64);
+            let mut payload_bytes = vec![0u8; i * 32];
+            rand_chacha::ChaChaRng::seed_from_u64(vector_seed).fill_bytes(&mut payload_bytes);
+            let record = crate::test_helpers::dummy_record_with_payload(vector_seed, &payload_bytes);
+
+            let (serialized_record, final_sign_high) = Self::serialize(&record)?;
+            let mut elements = Vec::with_capacity(serialized_record.len());
+            for element in serialized_record.iter() {
+                elements.push(hex(&to_bytes![element.into_affine()]?));
+            }
+
+            vectors.push(serde_json::json!({
+                "seed": vector_seed,
+                "value": record.value,
+                "payload": hex(record.payload.as_ref()),
+                "birth_program_id": hex(&record.birth_program_id),
+                "death_program_id": hex(&record.death_program_id),
+                "serial_number_nonce": hex(&to_bytes![record.serial_number_nonce]?),
+                "commitment_randomness": hex(&to_bytes![record.commitment_randomness]?),
+                "elements": elements,
+                "final_sign_high": final_sign_high,
+            }));
+        }
+
+        serde_json::to_string_pretty(&serde_json::json!({ "seed": seed, "vectors": vectors }))
+            .map_err(|error| DPCError::Crate("serde_json", error.to_string()))
+    }
+
+    /// Runs the precondition checks of `serialize` without performing the encoding,
+    /// returning the first failing reason.
+    ///
+    /// A record that passes still performs the full group-element encoding when it is
+    /// actually serialized; this only rules out the failures that are a pure function
+    /// of the record's fields, so a validation phase can reject bad records cheaply.
+    pub fn can_serialize(record: &Record) -> Result<(), DPCError> {
+        if record.payload.len() > Payload::CAPACITY {
+            return Err(DPCError::PayloadTooLarge(record.payload.len(), Payload::CAPACITY));
+        }
+
+        if !Self::value_width_fits(record.payload.len(), Self::VALUE_BITSIZE) {
+            return Err(RecordError::ValueDoesNotFit(
+                Self::VALUE_BITSIZE,
+                Self::element_count_for(record.payload.len()) - 1,
+            )
+            .into());
+        }
+
+        if Affine::from_random_bytes(&to_bytes![record.serial_number_nonce]?).is_none() {
+            return Err(DPCError::Message(
+                "the serial number nonce does not encode into the group".to_string(),
+            ));
+        }
+
+        read_program_id(&record.birth_program_id)
+            .map_err(|_| RecordError::InvalidProgramId("the birth program id is not an outer field element".to_string()))?;
+        read_program_id(&record.death_program_id)
+            .map_err(|_| RecordError::InvalidProgramId("the death program id is not an outer field element".to_string()))?;
+
+        Self::validate_commitment_randomness(&record.commitment_randomness)?;
+
+        Ok(())
+    }
+
+    /// Samples a fresh serial number nonce whose bytes recover to a group element, so
+    /// `serialize` is guaranteed to accept it.
+    ///
+    /// `from_random_bytes` succeeds for roughly half of the field, so candidates are
+    /// rejection-sampled; the retry cap only trips on a broken entropy source, where
+    /// failing beats spinning forever.
+    pub fn random_serial_number_nonce<R: rand::Rng>(rng: &mut R) -> Result<SerialNumberNonce, DPCError> {
+        for _ in 0..256 {
+            let candidate = SerialNumberNonce::rand(rng);
+            if Affine::from_random_bytes(&to_bytes![candidate]?).is_some() {
+                return Ok(candidate);
+            }
+        }
+        Err(DPCError::Message(
+            "failed to sample an encodable serial number nonce in 256 attempts".to_string(),
+        ))
+    }
+
+    /// Returns an error if the given commitment randomness sets any bit at or above
+    /// `SCALAR_FIELD_BITSIZE` in its byte serialization.
+    ///
+    /// `encode_to_group` relies on Assumption 1 to fit the randomness bytes into one
+    /// inner field element; randomness built from external entropy that does not reduce
+    /// into the scalar field would be silently truncated on decode, so this catches it
+    /// before it corrupts a record.
+    pub fn validate_commitment_randomness(randomness: &CommitmentRandomness) -> Result<(), DPCError> {
+        let randomness_bits = bytes_to_bits(&to_bytes![randomness]?);
+        if randomness_bits[Self::SCALAR_FIELD_BITSIZE..].iter().any(|bit| *bit) {
+            return Err(DPCError::Message(format!(
+                "the commitment randomness does not fit within {} scalar field bits",
+                Self::SCALAR_FIELD_BITSIZE
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the given bytes parse as a valid outer field element of the
+    /// expected length, i.e. they are usable as a birth or death program id.
+    pub fn is_valid_program_id(bytes: &[u8]) -> bool {
+        bytes.len() == (Self::OUTER_FIELD_BITSIZE + 7) / 8 && OuterField::read(bytes).is_ok()
+    }
+
+    /// Recovers a legacy program id of the given byte length from its zero-extended
+    /// full-width form, validating that the stripped bytes are in fact the padding.
+    #[cfg(feature = "legacy-program-ids")]
+    pub fn strip_program_id_padding(bytes: &[u8], len: usize) -> Result<Vec<u8>, DPCError> {
+        if len > bytes.len() || bytes[len..].iter().any(|byte| *byte != 0) {
+            return Err(RecordError::InvalidProgramId(format!(
+                "the program id does not zero-extend a {}-byte legacy id",
+                len
+            ))
+            .into());
+        }
+        Ok(bytes[..len].to_vec())
+    }
+
+    /// Returns the number of bytes the given record occupies when serialized with
+    /// uncompressed elements: `ELEMENT_BYTES` per group element, plus one byte for the
+    /// final sign bit. No serialization is performed.
+    pub fn serialized_byte_len(record: &Record) -> usize {
+        Self::serialized_len(record) * crate::packed::ELEMENT_BYTES + 1
+    }
+
+    /// Returns the number of bytes the given record occupies in the compressed form of
+    /// `serialize_compressed`: one x-coordinate per element, the per-element recovery
+    /// bits packed into bytes, plus one byte for the final sign bit.
+    pub fn serialized_byte_len_compressed(record: &Record) -> usize {
+        let element_count = Self::serialized_len(record);
+        element_count * (crate::packed::ELEMENT_BYTES / 2) + (element_count + 7) / 8 + 1
+    }
+
+    /// Returns the number of group elements `serialize` will produce for the given record.
+    pub fn serialized_len(record: &Record) -> usize {
+        Self::element_count_for(record.payload().len())
+    }
+}
+
+/// Rejects serialized records too short to decode: an empty input gets its own error,
+/// since a validator of user-uploaded files wants to report it distinctly from a
+/// truncated record.
+fn check_serialized_len(serialized_record: &[Group]) -> Result<(), DPCError> {
+    if serialized_record.is_empty() {
+        return Err(DPCError::EmptyRecord);
+    }
+    if serialized_record.len() < 6 {
+        return Err(RecordError::ShortSerialization(serialized_record.len()).into());
+    }
+    Ok(())
+}
+
+/// Extracts the per-element sign bits that follow the reserved bit of the final element,
+/// validating that enough bits were decoded for the given serialized element count.
+pub(crate) fn extract_fq_high_bits(final_element_bits: &[bool], expected_len: usize) -> Result<&[bool], DPCError> {
+    if final_element_bits.len() < expected_len {
+        return Err(RecordError::ShortFinalElement.into());
+    }
+    Ok(&final_element_bits[1..expected_len])
+}
+
+/// Hashes the given input with BLAKE2s under the given 32-byte seed.
+pub(crate) fn blake2s_hash(seed: &[u8; 32], input: &[u8]) -> Result<[u8; 32], DPCError> {
+    Blake2s::evaluate(seed, &input.to_vec())
+        .map_err(|error| DPCError::Crate("snarkvm-algorithms", error.to_string()))
+}
+
+/// Reads a program id as an outer field element.
+///
+/// With the `legacy-program-ids` feature enabled, program ids shorter than the field
+/// width are zero-extended on their most significant end before the read, so legacy
+/// records can be encoded without re-minting. Decode always yields full-width ids; use
+/// `RecordEncoder::strip_program_id_padding` with the known legacy length to recover
+/// the original bytes exactly.
+pub(crate) fn read_program_id(bytes: &[u8]) -> Result<OuterField, DPCError> {
+    #[cfg(feature = "legacy-program-ids")]
+    {
+        let width = (RecordEncoder::OUTER_FIELD_BITSIZE + 7) / 8;
+        if bytes.len() < width {
+            // The byte order is little-endian, so the most significant bytes are the
+            // trailing ones.
+            let mut padded = bytes.to_vec();
+            padded.resize(width, 0);
+            return Ok(OuterField::read(&padded[..])?);
+        }
+    }
+    Ok(OuterField::read(bytes)?)
+}
+
+/// Terminates the buffered payload bits with a reserved `1` bit (Assumption 4), so the
+/// element is nonzero and the payload bit count is recoverable on decode, then encodes
+/// them into one element and clears the buffer.
+fn push_payload_element(
+    payload_field_bits: &mut Vec<bool>,
+    data_elements: &mut Vec<Affine>,
+    data_high_bits: &mut Vec<bool>,
+) -> Result<(), DPCError> {
+    payload_field_bits.push(true);
+
+    let (encoded_payload_field, sign_high) = encode_to_group(&bits_to_bytes(payload_field_bits))?;
+    data_elements.push(encoded_payload_field);
+    data_high_bits.push(sign_high);
+    trace_encoding!(element = data_elements.len() - 1, sign_high, "encoded payload element");
+
+    payload_field_bits.clear();
+    Ok(())
+}
+
+/// Checks the element-count invariants that `serialize` maintains after each stage.
+///
+/// These are load-bearing correctness checks, so they are enforced unconditionally rather
+/// than with debug assertions.
+fn ensure_element_count(data_elements: &[Affine], data_high_bits: &[bool], expected: usize) -> Result<(), DPCError> {
+    if data_elements.len() != expected {
+        return Err(DPCError::EncodingInvariant {
+            expected,
+            got: data_elements.len(),
+        });
+    }
+    if data_high_bits.len() != expected {
+        return Err(DPCError::EncodingInvariant {
+            expected,
+            got: data_high_bits.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Encodes the given bytes into a group element and its sign bit.
+pub(crate) fn encode_to_group(bytes: &[u8]) -> Result<(Affine, bool), DPCError> {
+    Elligator2::<Parameters, Group>::encode(&bytes.to_vec())
+        .map_err(|error| DPCError::Crate("snarkvm-algorithms", error.to_string()))
+}
+
+/// Decodes a group element back into the bytes it encodes, given its sign bit.
+pub(crate) fn decode_from_group(group_element: Affine, fq_high: bool) -> Result<Vec<u8>, DPCError> {
+    Elligator2::<Parameters, Group>::decode(&group_element, fq_high)
+        .map_err(|error| DPCError::Crate("snarkvm-algorithms", error.to_string()))
+}
+
+/// Returns the index of the terminator bit, i.e. the last set bit, of a payload-carrying
+/// element's bits. The bits before it are payload bits; the bits after it are zero padding.
+pub(crate) fn payload_terminator_position(bits: &[bool]) -> Result<usize, DPCError> {
+    bits.iter()
+        .rposition(|bit| *bit)
+        .ok_or_else(|| DPCError::Message("the payload terminator bit is missing".to_string()))
+}
@@ -0,0 +1,107 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DPCError {
+    #[error("{}: {}", _0, _1)]
+    Crate(&'static str, String),
+
+    #[error("the reserved bit of serialized element {} is corrupt", element_index)]
+    CorruptReservedBit { element_index: usize },
+
+    #[error("the program id {} is not in the allowed set", _0)]
+    DisallowedProgram(String),
+
+    #[error("the serialized record is empty")]
+    EmptyRecord,
+
+    #[error("encoding invariant violated: expected {} data elements, found {}", expected, got)]
+    EncodingInvariant { expected: usize, got: usize },
+
+    #[error("{}", _0)]
+    Message(String),
+
+    #[error("a payload write of {} bytes at offset {} runs past the payload length of {} bytes", _0, _1, _2)]
+    PayloadOverflow(usize, usize, usize),
+
+    #[error("the record payload is {} bytes, which exceeds the payload capacity of {} bytes", _0, _1)]
+    PayloadTooLarge(usize, usize),
+
+    #[error("a payload read of {} bytes at offset {} runs past the payload length of {} bytes", _0, _1, _2)]
+    PayloadUnderflow(usize, usize, usize),
+
+    #[error("{}", _0)]
+    Record(#[from] RecordError),
+
+    #[error("unsupported record format version {}, supported version is {}", got, supported)]
+    UnsupportedVersion { got: u8, supported: u8 },
+
+    #[error("the record value {} exceeds the maximum allowed value {}", _0, _1)]
+    ValueOutOfRange(u64, u64),
+}
+
+/// Errors local to record encoding and decoding.
+///
+/// These are carried inside the broader [`DPCError`] via its `Record` variant, so callers
+/// can match record-layer failures precisely while public signatures remain compatible
+/// with the rest of the DPC stack.
+#[derive(Debug, Error)]
+pub enum RecordError {
+    #[error("checksum mismatch on the serialized record")]
+    ChecksumMismatch,
+
+    #[error("the number of sign bits does not match the number of serialized elements")]
+    FqHighBitsMismatch,
+
+    #[error("invalid program id: {}", _0)]
+    InvalidProgramId(String),
+
+    #[error("{}", _0)]
+    Other(String),
+
+    #[error("the record payload is {} bytes, which exceeds the payload capacity of {} bytes", _0, _1)]
+    PayloadTooLarge(usize, usize),
+
+    #[error("the final element holds too few bits to recover the sign bit ledger")]
+    ShortFinalElement,
+
+    #[error("the serialized record holds only {} group elements", _0)]
+    ShortSerialization(usize),
+
+    #[error("the value bits end at bit {}, but the final element holds only {} bits", _0, _1)]
+    ValueBitsOutOfRange(usize, usize),
+
+    #[error("the final element cannot hold a {}-bit value alongside the sign bits of {} elements", _0, _1)]
+    ValueDoesNotFit(usize, usize),
+}
+
+impl From<DPCError> for RecordError {
+    fn from(error: DPCError) -> Self {
+        match error {
+            DPCError::Record(error) => error,
+            DPCError::PayloadTooLarge(len, capacity) => RecordError::PayloadTooLarge(len, capacity),
+            error => RecordError::Other(error.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for DPCError {
+    fn from(error: std::io::Error) -> Self {
+        DPCError::Crate("std::io", error.to_string())
+    }
+}
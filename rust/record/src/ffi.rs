@@ -0,0 +1,124 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+    decoded::DecodedRecord,
+    encoder::RecordEncoder,
+    errors::DPCError,
+    payload::Payload,
+    record::{CommitmentRandomness, SerialNumberNonce},
+};
+
+use snarkvm_utilities::{to_bytes, FromBytes, ToBytes};
+
+/// The byte width of a full program id, i.e. of an outer field element.
+pub const PROGRAM_ID_BYTES: usize = (RecordEncoder::OUTER_FIELD_BITSIZE + 7) / 8;
+
+/// The byte width of a serial number nonce or commitment randomness.
+pub const FIELD_BYTES: usize = 32;
+
+/// A flat, fixed-ABI view of a [`DecodedRecord`] for crossing an FFI boundary.
+///
+/// The fixed-width fields are stored inline; the variable-length payload is a borrowed
+/// pointer and length into the source record, so a `CDecodedRecord` must not outlive
+/// the `DecodedRecord` it was built from. An empty payload carries a null pointer.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CDecodedRecord {
+    pub value: u64,
+    pub payload_ptr: *const u8,
+    pub payload_len: usize,
+    pub birth_program_id: [u8; PROGRAM_ID_BYTES],
+    pub death_program_id: [u8; PROGRAM_ID_BYTES],
+    pub serial_number_nonce: [u8; FIELD_BYTES],
+    pub commitment_randomness: [u8; FIELD_BYTES],
+}
+
+impl DecodedRecord {
+    /// Builds the flat FFI view of this record, borrowing the payload bytes in place.
+    ///
+    /// Errors if a program id is not exactly `PROGRAM_ID_BYTES` wide, since the C
+    /// struct has no room to record a shorter length.
+    pub fn to_c(&self) -> Result<CDecodedRecord, DPCError> {
+        let fixed = |bytes: &[u8], name: &str| -> Result<[u8; PROGRAM_ID_BYTES], DPCError> {
+            if bytes.len() != PROGRAM_ID_BYTES {
+                return Err(DPCError::Message(format!(
+                    "the {} is {} bytes, but the C layout requires {} bytes",
+                    name,
+                    bytes.len(),
+                    PROGRAM_ID_BYTES
+                )));
+            }
+            let mut array = [0u8; PROGRAM_ID_BYTES];
+            array.copy_from_slice(bytes);
+            Ok(array)
+        };
+
+        let mut serial_number_nonce = [0u8; FIELD_BYTES];
+        serial_number_nonce.copy_from_slice(&to_bytes![self.serial_number_nonce]?);
+        let mut commitment_randomness = [0u8; FIELD_BYTES];
+        commitment_randomness.copy_from_slice(&to_bytes![self.commitment_randomness]?);
+
+        Ok(CDecodedRecord {
+            value: self.value,
+            payload_ptr: if self.payload.is_empty() {
+                std::ptr::null()
+            } else {
+                self.payload.as_ref().as_ptr()
+            },
+            payload_len: self.payload.len(),
+            birth_program_id: fixed(&self.birth_program_id, "birth program id")?,
+            death_program_id: fixed(&self.death_program_id, "death program id")?,
+            serial_number_nonce,
+            commitment_randomness,
+        })
+    }
+
+    /// Rebuilds an owned record from the flat FFI view, copying the payload bytes out.
+    ///
+    /// The payload length is validated against the payload capacity, and a non-empty
+    /// payload with a null pointer is rejected rather than dereferenced.
+    ///
+    /// # Safety
+    ///
+    /// If `c_record.payload_len` is nonzero, `c_record.payload_ptr` must point to
+    /// `payload_len` readable bytes that stay valid for the duration of the call.
+    pub unsafe fn from_c(c_record: &CDecodedRecord) -> Result<DecodedRecord, DPCError> {
+        if c_record.payload_len > Payload::CAPACITY {
+            return Err(DPCError::PayloadTooLarge(c_record.payload_len, Payload::CAPACITY));
+        }
+        let payload = if c_record.payload_len == 0 {
+            Payload::default()
+        } else if c_record.payload_ptr.is_null() {
+            return Err(DPCError::Message(format!(
+                "the C record payload pointer is null, but its length is {}",
+                c_record.payload_len
+            )));
+        } else {
+            Payload::from_bytes(std::slice::from_raw_parts(c_record.payload_ptr, c_record.payload_len))
+        };
+
+        Ok(DecodedRecord {
+            value: c_record.value,
+            payload,
+            birth_program_id: c_record.birth_program_id.to_vec(),
+            death_program_id: c_record.death_program_id.to_vec(),
+            serial_number_nonce: SerialNumberNonce::read(&c_record.serial_number_nonce[..])?,
+            commitment_randomness: CommitmentRandomness::read(&c_record.commitment_randomness[..])?,
+            owner: None,
+        })
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+// The `ffi` feature marshals payload bytes through raw pointers, so the crate can only
+// forbid unsafe code outright when it is disabled.
+#![cfg_attr(not(feature = "ffi"), forbid(unsafe_code))]
+
+pub mod codec;
+pub use codec::*;
+
+pub mod columnar;
+pub use columnar::*;
+
+pub mod decoded;
+pub use decoded::*;
+
+pub mod encoder;
+pub use encoder::*;
+
+pub mod errors;
+pub use errors::*;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "ffi")]
+pub use ffi::*;
+
+pub mod merkle;
+pub use merkle::*;
+
+pub mod packed;
+pub use packed::*;
+
+pub mod payload;
+pub use payload::*;
+
+pub mod record;
+pub use record::*;
+
+#[cfg(feature = "schema")]
+pub mod schema;
+#[cfg(feature = "schema")]
+pub use schema::*;
+
+pub mod serialized;
+pub use serialized::*;
+
+#[cfg(any(test, feature = "test-helpers"))]
+pub mod test_helpers;
+
+#[cfg(test)]
+pub mod tests;
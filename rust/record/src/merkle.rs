@@ -0,0 +1,99 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{encoder::blake2s_hash, errors::DPCError};
+
+/// The BLAKE2s domain separator for interior nodes of the commitment tree.
+const MERKLE_NODE_DOMAIN: &[u8] = b"AleoRecordMerkleNode";
+
+/// The root of a commitment tree built by `RecordEncoder::commitment_tree`.
+pub type MerkleRoot = [u8; 32];
+
+/// An inclusion proof for one leaf of a commitment tree.
+///
+/// The siblings run from the leaf level up to the level below the root; at each level,
+/// the bit of `leaf_index` selects which side the sibling sits on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerklePath {
+    pub leaf_index: usize,
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl MerklePath {
+    /// Returns `true` if hashing `leaf` up through the siblings reproduces `root`.
+    pub fn verify(&self, leaf: &[u8; 32], root: &MerkleRoot) -> Result<bool, DPCError> {
+        let mut node = *leaf;
+        for (level, sibling) in self.siblings.iter().enumerate() {
+            node = if self.leaf_index >> level & 1 == 0 {
+                hash_node(&node, sibling)?
+            } else {
+                hash_node(sibling, &node)?
+            };
+        }
+        Ok(node == *root)
+    }
+}
+
+/// Hashes two child nodes into their parent under the node domain separator.
+fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> Result<[u8; 32], DPCError> {
+    let mut seed = [0u8; 32];
+    seed[..MERKLE_NODE_DOMAIN.len()].copy_from_slice(MERKLE_NODE_DOMAIN);
+
+    let mut input = [0u8; 64];
+    input[..32].copy_from_slice(left);
+    input[32..].copy_from_slice(right);
+    blake2s_hash(&seed, &input)
+}
+
+/// Builds a binary BLAKE2s Merkle tree over the given leaves, returning the root and
+/// one inclusion proof per leaf, in leaf order.
+///
+/// The leaf count is padded to the next power of two with all-zero leaves, so every
+/// path has the same depth and the tree shape is a pure function of the leaf count.
+pub(crate) fn build_commitment_tree(leaves: &[[u8; 32]]) -> Result<(MerkleRoot, Vec<MerklePath>), DPCError> {
+    if leaves.is_empty() {
+        return Err(DPCError::Message("cannot build a commitment tree over zero records".to_string()));
+    }
+
+    let width = leaves.len().next_power_of_two();
+    let mut level: Vec<[u8; 32]> = leaves.to_vec();
+    level.resize(width, [0u8; 32]);
+
+    // Record every level bottom-up, so sibling lookups are direct index reads.
+    let mut levels = vec![level];
+    while levels[levels.len() - 1].len() > 1 {
+        let below = &levels[levels.len() - 1];
+        let mut above = Vec::with_capacity(below.len() / 2);
+        for pair in below.chunks(2) {
+            above.push(hash_node(&pair[0], &pair[1])?);
+        }
+        levels.push(above);
+    }
+    let root = levels[levels.len() - 1][0];
+
+    let paths = (0..leaves.len())
+        .map(|leaf_index| MerklePath {
+            leaf_index,
+            siblings: levels[..levels.len() - 1]
+                .iter()
+                .enumerate()
+                .map(|(depth, level)| level[(leaf_index >> depth) ^ 1])
+                .collect(),
+        })
+        .collect();
+
+    Ok((root, paths))
+}
@@ -0,0 +1,96 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+    decoded::DecodedRecord,
+    encoder::{Affine, RecordEncoder},
+    errors::DPCError,
+    payload::Payload,
+    record::Record,
+};
+
+use snarkvm_curves::traits::{AffineCurve, ProjectiveCurve};
+use snarkvm_utilities::{to_bytes, FromBytes, ToBytes};
+
+/// The number of bytes one uncompressed affine group element occupies: the x- and
+/// y-coordinates of the twisted Edwards point, 32 bytes each.
+pub const ELEMENT_BYTES: usize = 64;
+
+/// The maximum number of group elements a serialized record can occupy: the five fixed
+/// elements, one element per `PAYLOAD_ELEMENT_BITSIZE` payload bits at full capacity,
+/// the extra element of the `value_does_not_fit` case, and the final element.
+pub const MAX_ELEMENTS: usize = 5 + (Payload::CAPACITY * 8) / RecordEncoder::PAYLOAD_ELEMENT_BITSIZE + 2;
+
+/// A serialized record in a fixed, alignment-safe layout with stable offsets, for
+/// contiguous on-disk storage and mmap-based access.
+///
+/// The layout reserves space for `MAX_ELEMENTS` group elements regardless of the
+/// payload size; `element_count` records how many leading slots are in use, and the
+/// unused slots are zeroed. Every field is plain bytes or a fixed-width integer, so a
+/// `PackedRecord` can be read back at a fixed offset without per-record parsing.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct PackedRecord {
+    /// The number of leading element slots in use.
+    pub element_count: u32,
+    /// The sign bit of the final element, stored as `0` or `1`.
+    pub final_sign_high: u8,
+    /// The uncompressed affine bytes of each group element.
+    pub elements: [[u8; ELEMENT_BYTES]; MAX_ELEMENTS],
+}
+
+impl PackedRecord {
+    /// Serializes the given record into the fixed layout.
+    pub fn from_record(record: &Record) -> Result<PackedRecord, DPCError> {
+        let (serialized_record, final_sign_high) = RecordEncoder::serialize(record)?;
+        if serialized_record.len() > MAX_ELEMENTS {
+            return Err(DPCError::EncodingInvariant {
+                expected: MAX_ELEMENTS,
+                got: serialized_record.len(),
+            });
+        }
+
+        let mut elements = [[0u8; ELEMENT_BYTES]; MAX_ELEMENTS];
+        for (slot, element) in elements.iter_mut().zip(serialized_record.iter()) {
+            let element_bytes = to_bytes![element.into_affine()]?;
+            slot.copy_from_slice(&element_bytes);
+        }
+
+        Ok(PackedRecord {
+            element_count: serialized_record.len() as u32,
+            final_sign_high: final_sign_high as u8,
+            elements,
+        })
+    }
+
+    /// Decodes the record stored in the fixed layout.
+    pub fn to_decoded(&self) -> Result<DecodedRecord, DPCError> {
+        let element_count = self.element_count as usize;
+        if element_count > MAX_ELEMENTS {
+            return Err(DPCError::EncodingInvariant {
+                expected: MAX_ELEMENTS,
+                got: element_count,
+            });
+        }
+
+        let mut serialized_record = Vec::with_capacity(element_count);
+        for slot in self.elements[..element_count].iter() {
+            serialized_record.push(Affine::read(&slot[..])?.into_projective());
+        }
+
+        RecordEncoder::deserialize(&serialized_record, self.final_sign_high != 0)
+    }
+}
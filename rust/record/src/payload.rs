@@ -0,0 +1,752 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::errors::DPCError;
+
+use rand::Rng;
+use snarkvm_curves::traits::{AffineCurve, ProjectiveCurve};
+use snarkvm_utilities::{to_bytes, FromBytes, ToBytes, UniformRand};
+
+use std::{io::Read, ops::Range};
+
+/// The variable-length data blob carried by a record.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Payload {
+    bytes: Vec<u8>,
+}
+
+impl Payload {
+    /// The maximum number of payload bytes a single record may carry.
+    ///
+    /// This bound keeps the number of payload elements small enough that the per-element
+    /// sign bit ledger always fits in the final element alongside the value bits.
+    pub const CAPACITY: usize = 4096;
+
+    /// The maximum number of memo bytes the reserved memo region can carry.
+    pub const MEMO_CAPACITY: usize = 64;
+
+    /// Constructs a payload from the given bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self { bytes: bytes.to_vec() }
+    }
+
+    /// Constructs a payload by reading all remaining bytes from the given reader.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, DPCError> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        Ok(Self { bytes })
+    }
+
+    /// Constructs a payload by reading exactly `len` bytes from the given reader,
+    /// leaving any following bytes in the reader untouched.
+    ///
+    /// Errors if `len` exceeds the payload capacity or the reader ends early, so a
+    /// framed stream is never over-read into the next frame.
+    pub fn read_exact<R: Read>(reader: &mut R, len: usize) -> Result<Self, DPCError> {
+        if len > Self::CAPACITY {
+            return Err(DPCError::PayloadTooLarge(len, Self::CAPACITY));
+        }
+        let mut bytes = vec![0u8; len];
+        reader.read_exact(&mut bytes)?;
+        Ok(Self { bytes })
+    }
+
+    /// Returns the payload bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.bytes.clone()
+    }
+
+    /// Returns the number of payload bytes.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns `true` if the payload contains no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Returns `true` if every payload byte is zero. This does not allocate, and an
+    /// empty payload is considered zero.
+    pub fn is_zero(&self) -> bool {
+        self.bytes.iter().all(|byte| *byte == 0)
+    }
+
+    /// Returns the number of occurrences of each byte value in the payload.
+    pub fn byte_histogram(&self) -> [u32; 256] {
+        let mut histogram = [0u32; 256];
+        for byte in &self.bytes {
+            histogram[*byte as usize] += 1;
+        }
+        histogram
+    }
+
+    /// Returns the Shannon entropy of the payload bytes in bits per byte, between `0.0`
+    /// and `8.0`. An empty payload has entropy `0.0`.
+    ///
+    /// This is a cheap compressibility estimate: entropy near `8.0` means the
+    /// compression codec is unlikely to help.
+    pub fn shannon_entropy(&self) -> f64 {
+        if self.bytes.is_empty() {
+            return 0.0;
+        }
+        let len = self.bytes.len() as f64;
+        self.byte_histogram()
+            .iter()
+            .filter(|count| **count > 0)
+            .map(|count| {
+                let probability = *count as f64 / len;
+                -probability * probability.log2()
+            })
+            .sum()
+    }
+}
+
+impl Payload {
+    /// Constructs a payload holding a length-prefixed list of byte segments.
+    ///
+    /// The framing is a little-endian `u16` segment count, followed by each segment as a
+    /// little-endian `u16` length and its bytes. The combined length must fit within the
+    /// payload capacity.
+    pub fn from_segments(segments: &[&[u8]]) -> Result<Payload, DPCError> {
+        if segments.len() > u16::MAX as usize {
+            return Err(DPCError::Message(format!("too many payload segments: {}", segments.len())));
+        }
+
+        let mut bytes = Vec::with_capacity(2 + segments.iter().map(|segment| 2 + segment.len()).sum::<usize>());
+        bytes.extend_from_slice(&(segments.len() as u16).to_le_bytes());
+        for segment in segments {
+            if segment.len() > u16::MAX as usize {
+                return Err(DPCError::Message(format!("payload segment of {} bytes is too long", segment.len())));
+            }
+            bytes.extend_from_slice(&(segment.len() as u16).to_le_bytes());
+            bytes.extend_from_slice(segment);
+        }
+
+        if bytes.len() > Self::CAPACITY {
+            return Err(DPCError::PayloadTooLarge(bytes.len(), Self::CAPACITY));
+        }
+
+        Ok(Self { bytes })
+    }
+
+    /// Recovers the byte segments stored by `from_segments`.
+    pub fn segments(&self) -> Result<Vec<Vec<u8>>, DPCError> {
+        let truncated = || DPCError::Message("the payload segment framing is truncated".to_string());
+
+        let mut offset = 0usize;
+        let mut read_u16 = |bytes: &[u8], offset: &mut usize| -> Result<usize, DPCError> {
+            let end = offset.checked_add(2).filter(|end| *end <= bytes.len()).ok_or_else(truncated)?;
+            let value = u16::from_le_bytes([bytes[*offset], bytes[*offset + 1]]) as usize;
+            *offset = end;
+            Ok(value)
+        };
+
+        let count = read_u16(&self.bytes, &mut offset)?;
+        let mut segments = Vec::with_capacity(count);
+        for _ in 0..count {
+            let len = read_u16(&self.bytes, &mut offset)?;
+            let end = offset.checked_add(len).filter(|end| *end <= self.bytes.len()).ok_or_else(truncated)?;
+            segments.push(self.bytes[offset..end].to_vec());
+            offset = end;
+        }
+
+        Ok(segments)
+    }
+
+    /// Constructs a payload holding the given data behind a random salt, so records
+    /// with identical data no longer encode to equal payloads.
+    ///
+    /// The framing is a little-endian `u16` salt length, the salt bytes, and the data.
+    /// The salt is never interpreted — `strip_salt` skips it — so any length up to
+    /// `u16::MAX` works, as long as the framed whole fits the payload capacity.
+    pub fn with_salt<R: Rng>(data: &[u8], salt_len: usize, rng: &mut R) -> Result<Payload, DPCError> {
+        if salt_len > u16::MAX as usize {
+            return Err(DPCError::Message(format!("the salt length {} exceeds the u16 prefix", salt_len)));
+        }
+        let framed_len = 2 + salt_len + data.len();
+        if framed_len > Self::CAPACITY {
+            return Err(DPCError::PayloadTooLarge(framed_len, Self::CAPACITY));
+        }
+
+        let mut bytes = Vec::with_capacity(framed_len);
+        bytes.extend_from_slice(&(salt_len as u16).to_le_bytes());
+        bytes.resize(2 + salt_len, 0);
+        rng.fill_bytes(&mut bytes[2..]);
+        bytes.extend_from_slice(data);
+        Ok(Self { bytes })
+    }
+
+    /// Recovers the data stored by `with_salt`, discarding the salt.
+    pub fn strip_salt(&self) -> Result<Vec<u8>, DPCError> {
+        if self.bytes.len() < 2 {
+            return Err(DPCError::Message("the salted payload framing is truncated".to_string()));
+        }
+        let salt_len = u16::from_le_bytes([self.bytes[0], self.bytes[1]]) as usize;
+        if 2 + salt_len > self.bytes.len() {
+            return Err(DPCError::Message(format!(
+                "the salted payload records a {}-byte salt, but holds only {} bytes",
+                salt_len,
+                self.bytes.len()
+            )));
+        }
+        Ok(self.bytes[2 + salt_len..].to_vec())
+    }
+
+    /// Constructs a payload from a UTF-8 string, zero-padded to a multiple of 8 bytes.
+    ///
+    /// The padding is stripped again by `to_trimmed_string`, so text payloads round-trip
+    /// as long as the text itself does not end in a NUL character.
+    pub fn from_str_padded(s: &str) -> Result<Payload, DPCError> {
+        let mut bytes = s.as_bytes().to_vec();
+        let padded_len = (bytes.len() + 7) / 8 * 8;
+        if padded_len > Self::CAPACITY {
+            return Err(DPCError::PayloadTooLarge(padded_len, Self::CAPACITY));
+        }
+        bytes.resize(padded_len, 0);
+        Ok(Self { bytes })
+    }
+
+    /// Recovers the string stored by `from_str_padded`, trimming trailing zero padding
+    /// and validating that the remaining bytes are UTF-8.
+    pub fn to_trimmed_string(&self) -> Result<String, DPCError> {
+        let end = self.bytes.iter().rposition(|byte| *byte != 0).map_or(0, |index| index + 1);
+        String::from_utf8(self.bytes[..end].to_vec())
+            .map_err(|error| DPCError::Message(format!("the payload is not valid UTF-8: {}", error)))
+    }
+
+    /// Overwrites the payload bytes at `[offset..offset + data.len()]` with `data`.
+    ///
+    /// The write is bounds-checked against the payload length, which never changes.
+    pub fn patch(&mut self, offset: usize, data: &[u8]) -> Result<(), DPCError> {
+        let end = offset.checked_add(data.len()).ok_or_else(|| {
+            DPCError::Message(format!("payload patch at offset {} overflows", offset))
+        })?;
+        if end > self.bytes.len() {
+            return Err(DPCError::Message(format!(
+                "payload patch of {} bytes at offset {} exceeds the payload length of {} bytes",
+                data.len(),
+                offset,
+                self.bytes.len()
+            )));
+        }
+        self.bytes[offset..end].copy_from_slice(data);
+        Ok(())
+    }
+
+    /// Stores a memo in the payload's reserved memo region, a fixed-size prefix of one
+    /// length byte followed by `MEMO_CAPACITY` memo bytes.
+    ///
+    /// The payload must already span the memo region, so reserving it is an explicit
+    /// choice of the payload's author rather than a silent resize. Unused memo bytes are
+    /// zeroed, and the free data after the region is left untouched.
+    pub fn set_memo(&mut self, memo: &[u8]) -> Result<(), DPCError> {
+        if memo.len() > Self::MEMO_CAPACITY {
+            return Err(DPCError::Message(format!(
+                "the memo is {} bytes, which exceeds the memo capacity of {} bytes",
+                memo.len(),
+                Self::MEMO_CAPACITY
+            )));
+        }
+        self.require_len(1 + Self::MEMO_CAPACITY)?;
+
+        self.bytes[0] = memo.len() as u8;
+        self.bytes[1..1 + memo.len()].copy_from_slice(memo);
+        for byte in self.bytes[1 + memo.len()..1 + Self::MEMO_CAPACITY].iter_mut() {
+            *byte = 0;
+        }
+        Ok(())
+    }
+
+    /// Returns the memo stored in the payload's memo region, or `None` if the payload
+    /// does not span the region or the length byte is out of range.
+    pub fn memo(&self) -> Option<&[u8]> {
+        if self.bytes.len() < 1 + Self::MEMO_CAPACITY {
+            return None;
+        }
+        let memo_len = self.bytes[0] as usize;
+        if memo_len > Self::MEMO_CAPACITY {
+            return None;
+        }
+        Some(&self.bytes[1..1 + memo_len])
+    }
+
+    /// Returns a copy of the payload limited to its first `max_len` bytes. A payload
+    /// already within the limit is returned unchanged, and a shorter payload is always
+    /// encodable, so truncation cannot break later serialization.
+    pub fn truncated(&self, max_len: usize) -> Payload {
+        Self {
+            bytes: self.bytes[..self.bytes.len().min(max_len)].to_vec(),
+        }
+    }
+
+    /// Truncates the payload in place to at most `max_len` bytes.
+    pub fn truncate(&mut self, max_len: usize) {
+        self.bytes.truncate(max_len);
+    }
+
+    /// Returns the byte-wise XOR of this payload with `other`, erroring if the lengths
+    /// differ. The result has the same length, so it always stays within capacity.
+    pub fn xor(&self, other: &Payload) -> Result<Payload, DPCError> {
+        if self.bytes.len() != other.bytes.len() {
+            return Err(DPCError::Message(format!(
+                "cannot XOR payloads of {} and {} bytes",
+                self.bytes.len(),
+                other.bytes.len()
+            )));
+        }
+        Ok(Self {
+            bytes: self.bytes.iter().zip(&other.bytes).map(|(a, b)| a ^ b).collect(),
+        })
+    }
+
+    /// Returns an error if the payload holds fewer than `len` bytes.
+    pub fn require_len(&self, len: usize) -> Result<(), DPCError> {
+        if self.bytes.len() < len {
+            return Err(DPCError::Message(format!(
+                "the payload holds {} bytes, but {} bytes are required",
+                self.bytes.len(),
+                len
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// The BLAKE2s domain separator for deriving a region key from an ECDH shared point.
+const REGION_KEY_DOMAIN: &[u8] = b"AleoPayloadRegionKey";
+
+impl Payload {
+    /// The byte width of the header `encrypt_region` writes at the start of a region:
+    /// the uncompressed affine bytes of the ephemeral public key.
+    pub const REGION_HEADER_BYTES: usize = 64;
+
+    /// Encrypts the payload bytes in `range` to the given recipient, in place.
+    ///
+    /// The scheme is ECIES over the encoding group: an ephemeral scalar is sampled, its
+    /// public key is written as a header over the leading `REGION_HEADER_BYTES` of the
+    /// region, and the remaining region bytes are XORed with a BLAKE2s keystream keyed
+    /// by the ECDH shared point. The payload length never changes, so the record's
+    /// serialized shape is unaffected; the header overwrites the leading region bytes,
+    /// so callers must reserve them rather than store plaintext there.
+    ///
+    /// `recipient_pubkey` is the recipient's public key as 64 uncompressed affine bytes.
+    pub fn encrypt_region<R: Rng>(
+        &mut self,
+        range: Range<usize>,
+        recipient_pubkey: &[u8],
+        rng: &mut R,
+    ) -> Result<(), DPCError> {
+        self.check_region(&range)?;
+        let recipient = crate::encoder::Affine::read(recipient_pubkey)
+            .map_err(|_| DPCError::Message("the recipient public key is not a group element".to_string()))?;
+
+        let ephemeral_scalar = crate::encoder::ScalarField::rand(rng);
+        let ephemeral_public = crate::encoder::Affine::prime_subgroup_generator()
+            .mul(ephemeral_scalar)
+            .into_affine();
+        let key = region_key(&recipient.mul(ephemeral_scalar).into_affine())?;
+
+        self.bytes[range.start..range.start + Self::REGION_HEADER_BYTES]
+            .copy_from_slice(&to_bytes![ephemeral_public]?);
+        apply_region_keystream(&key, &mut self.bytes[range.start + Self::REGION_HEADER_BYTES..range.end])?;
+        Ok(())
+    }
+
+    /// Decrypts a region encrypted by `encrypt_region`, in place, using the recipient's
+    /// private key as 32 scalar bytes.
+    ///
+    /// The ephemeral public key header is left in place, since the original bytes under
+    /// it were consumed by the encryption.
+    pub fn decrypt_region(&mut self, range: Range<usize>, private_key_bytes: &[u8]) -> Result<(), DPCError> {
+        self.check_region(&range)?;
+        let ephemeral_public =
+            crate::encoder::Affine::read(&self.bytes[range.start..range.start + Self::REGION_HEADER_BYTES])
+                .map_err(|_| DPCError::Message("the region header is not a group element".to_string()))?;
+        let private_key = crate::encoder::ScalarField::read(private_key_bytes)
+            .map_err(|_| DPCError::Message("the private key is not a scalar".to_string()))?;
+
+        let key = region_key(&ephemeral_public.mul(private_key).into_affine())?;
+        apply_region_keystream(&key, &mut self.bytes[range.start + Self::REGION_HEADER_BYTES..range.end])?;
+        Ok(())
+    }
+
+    /// Validates that an encryption region is in bounds and spans more than its header.
+    fn check_region(&self, range: &Range<usize>) -> Result<(), DPCError> {
+        if range.start > range.end || range.end > self.bytes.len() {
+            return Err(DPCError::Message(format!(
+                "the region {}..{} falls outside the payload length of {} bytes",
+                range.start,
+                range.end,
+                self.bytes.len()
+            )));
+        }
+        if range.end - range.start <= Self::REGION_HEADER_BYTES {
+            return Err(DPCError::Message(format!(
+                "the region {}..{} leaves no bytes after its {}-byte header",
+                range.start,
+                range.end,
+                Self::REGION_HEADER_BYTES
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Derives the 32-byte region key from an ECDH shared point.
+fn region_key(shared: &crate::encoder::Affine) -> Result<[u8; 32], DPCError> {
+    let mut seed = [0u8; 32];
+    seed[..REGION_KEY_DOMAIN.len()].copy_from_slice(REGION_KEY_DOMAIN);
+    crate::encoder::blake2s_hash(&seed, &to_bytes![shared]?)
+}
+
+/// XORs the given bytes with a BLAKE2s keystream under the given key, block by block.
+fn apply_region_keystream(key: &[u8; 32], bytes: &mut [u8]) -> Result<(), DPCError> {
+    for (block_index, block) in bytes.chunks_mut(32).enumerate() {
+        let keystream = crate::encoder::blake2s_hash(key, &(block_index as u32).to_le_bytes())?;
+        for (byte, pad) in block.iter_mut().zip(keystream.iter()) {
+            *byte ^= pad;
+        }
+    }
+    Ok(())
+}
+
+impl Payload {
+    /// Returns a reader that yields typed values sequentially from the start of the
+    /// payload.
+    pub fn cursor(&self) -> PayloadReader<'_> {
+        PayloadReader { payload: self, offset: 0 }
+    }
+
+    /// Returns a writer that stores typed values sequentially from the start of the
+    /// payload. Writes overwrite existing bytes in place; the payload length never
+    /// changes, so a write past the end is an error rather than a resize.
+    pub fn cursor_mut(&mut self) -> PayloadWriter<'_> {
+        PayloadWriter { payload: self, offset: 0 }
+    }
+}
+
+/// A sequential reader over payload bytes, tracking its own offset.
+///
+/// Every read is bounds-checked against the payload length; a read past the end returns
+/// `DPCError::PayloadUnderflow` and leaves the offset unchanged.
+pub struct PayloadReader<'a> {
+    payload: &'a Payload,
+    offset: usize,
+}
+
+impl<'a> PayloadReader<'a> {
+    /// Returns the current read offset.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the number of bytes left to read.
+    pub fn remaining(&self) -> usize {
+        self.payload.len() - self.offset
+    }
+
+    /// Reads the next `len` bytes.
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], DPCError> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|end| *end <= self.payload.len())
+            .ok_or(DPCError::PayloadUnderflow(len, self.offset, self.payload.len()))?;
+        let bytes = &self.payload.bytes[self.offset..end];
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    /// Reads the next byte.
+    pub fn read_u8(&mut self) -> Result<u8, DPCError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    /// Reads the next four bytes as a little-endian `u32`.
+    pub fn read_u32(&mut self) -> Result<u32, DPCError> {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(self.read_bytes(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    /// Reads the next eight bytes as a little-endian `u64`.
+    pub fn read_u64(&mut self) -> Result<u64, DPCError> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.read_bytes(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    /// Advances the offset by `len` bytes without yielding them.
+    pub fn skip(&mut self, len: usize) -> Result<(), DPCError> {
+        self.read_bytes(len).map(|_| ())
+    }
+}
+
+/// A sequential writer over payload bytes, tracking its own offset.
+///
+/// Every write is bounds-checked against the payload length; a write past the end
+/// returns `DPCError::PayloadOverflow` and leaves the payload and offset unchanged.
+pub struct PayloadWriter<'a> {
+    payload: &'a mut Payload,
+    offset: usize,
+}
+
+impl PayloadWriter<'_> {
+    /// Returns the current write offset.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the number of bytes left to write.
+    pub fn remaining(&self) -> usize {
+        self.payload.len() - self.offset
+    }
+
+    /// Writes the given bytes at the current offset.
+    pub fn write_bytes(&mut self, data: &[u8]) -> Result<(), DPCError> {
+        let end = self
+            .offset
+            .checked_add(data.len())
+            .filter(|end| *end <= self.payload.len())
+            .ok_or(DPCError::PayloadOverflow(data.len(), self.offset, self.payload.len()))?;
+        self.payload.bytes[self.offset..end].copy_from_slice(data);
+        self.offset = end;
+        Ok(())
+    }
+
+    /// Writes one byte at the current offset.
+    pub fn write_u8(&mut self, value: u8) -> Result<(), DPCError> {
+        self.write_bytes(&[value])
+    }
+
+    /// Writes a `u32` at the current offset in little-endian order.
+    pub fn write_u32(&mut self, value: u32) -> Result<(), DPCError> {
+        self.write_bytes(&value.to_le_bytes())
+    }
+
+    /// Writes a `u64` at the current offset in little-endian order.
+    pub fn write_u64(&mut self, value: u64) -> Result<(), DPCError> {
+        self.write_bytes(&value.to_le_bytes())
+    }
+
+    /// Advances the offset by `len` bytes, leaving the skipped bytes untouched.
+    pub fn skip(&mut self, len: usize) -> Result<(), DPCError> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|end| *end <= self.payload.len())
+            .ok_or(DPCError::PayloadOverflow(len, self.offset, self.payload.len()))?;
+        self.offset = end;
+        Ok(())
+    }
+}
+
+/// The compression codec applied to a payload by `Payload::from_compressed`.
+#[cfg(feature = "compression")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompressionCodec {
+    Deflate,
+}
+
+#[cfg(feature = "compression")]
+impl CompressionCodec {
+    /// The codec byte stored in the compressed payload header.
+    fn to_byte(self) -> u8 {
+        match self {
+            CompressionCodec::Deflate => 0,
+        }
+    }
+
+    /// Recovers the codec from its header byte.
+    fn from_byte(byte: u8) -> Result<Self, DPCError> {
+        match byte {
+            0 => Ok(CompressionCodec::Deflate),
+            byte => Err(DPCError::Message(format!("unknown payload compression codec {}", byte))),
+        }
+    }
+}
+
+#[cfg(feature = "compression")]
+impl Payload {
+    /// The largest original length a compressed payload may record in its header.
+    ///
+    /// `decompress` allocates and inflates up to this many bytes before validating the
+    /// header, so the bound caps what a crafted header can cost; 16x the payload
+    /// capacity is ample for data that actually deflates into a payload.
+    pub const MAX_DECOMPRESSED_LEN: usize = 16 * Self::CAPACITY;
+
+    /// Constructs a payload holding the given data in compressed form.
+    ///
+    /// The payload stores a five-byte header of the codec byte and the original length
+    /// as a little-endian `u32`, followed by the compressed bytes. The compressed result
+    /// must still fit the payload capacity, and the original length must stay within
+    /// `MAX_DECOMPRESSED_LEN` so `decompress` will accept the header.
+    pub fn from_compressed(data: &[u8], codec: CompressionCodec) -> Result<Payload, DPCError> {
+        use std::io::Write;
+
+        if data.len() > Self::MAX_DECOMPRESSED_LEN {
+            return Err(DPCError::PayloadTooLarge(data.len(), Self::MAX_DECOMPRESSED_LEN));
+        }
+
+        let compressed = match codec {
+            CompressionCodec::Deflate => {
+                let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()?
+            }
+        };
+
+        let mut bytes = Vec::with_capacity(5 + compressed.len());
+        bytes.push(codec.to_byte());
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&compressed);
+
+        if bytes.len() > Self::CAPACITY {
+            return Err(DPCError::PayloadTooLarge(bytes.len(), Self::CAPACITY));
+        }
+
+        Ok(Self { bytes })
+    }
+
+    /// Recovers the original data stored by `from_compressed`, validating the codec
+    /// header and the original length.
+    pub fn decompress(&self) -> Result<Vec<u8>, DPCError> {
+        if self.bytes.len() < 5 {
+            return Err(DPCError::Message("the compressed payload header is truncated".to_string()));
+        }
+        let codec = CompressionCodec::from_byte(self.bytes[0])?;
+        let original_len =
+            u32::from_le_bytes([self.bytes[1], self.bytes[2], self.bytes[3], self.bytes[4]]) as usize;
+
+        // The header is untrusted input: bound it before allocating, and cap the
+        // decoder one byte past the claim so a lying stream cannot inflate further.
+        if original_len > Self::MAX_DECOMPRESSED_LEN {
+            return Err(DPCError::PayloadTooLarge(original_len, Self::MAX_DECOMPRESSED_LEN));
+        }
+
+        let data = match codec {
+            CompressionCodec::Deflate => {
+                let mut data = Vec::with_capacity(original_len);
+                flate2::read::DeflateDecoder::new(&self.bytes[5..])
+                    .take(original_len as u64 + 1)
+                    .read_to_end(&mut data)?;
+                data
+            }
+        };
+
+        if data.len() != original_len {
+            return Err(DPCError::Message(format!(
+                "the compressed payload decompressed to {} bytes, but its header records {} bytes",
+                data.len(),
+                original_len
+            )));
+        }
+
+        Ok(data)
+    }
+}
+
+/// The payload framing features a caller intends to combine, for sizing a payload up
+/// front with `Payload::required_capacity_for`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PayloadFeatures {
+    /// Reserve the leading memo region consumed by `set_memo`.
+    pub memo: bool,
+    /// Frame the data as this many segments via `from_segments`; zero means unframed.
+    pub segment_count: usize,
+    /// Store the data through `from_compressed`, sized for incompressible input.
+    pub compressed: bool,
+    /// Reserve this many `encrypt_region` headers alongside the data.
+    pub encrypted_regions: usize,
+}
+
+impl Payload {
+    /// Returns the total payload bytes needed to store `data_len` bytes of data under
+    /// the given framing features.
+    ///
+    /// The estimate is an upper bound: the compression overhead assumes incompressible
+    /// input, which DEFLATE stores raw plus its header and one stored-block frame. The
+    /// result may exceed `CAPACITY`; comparing against it is the caller's decision.
+    pub fn required_capacity_for(data_len: usize, features: PayloadFeatures) -> usize {
+        // The header plus the stored-block framing DEFLATE falls back to on
+        // incompressible input, which covers any data within the payload capacity.
+        const COMPRESSION_OVERHEAD: usize = 5 + 5;
+
+        let mut capacity = data_len;
+        if features.memo {
+            capacity += 1 + Self::MEMO_CAPACITY;
+        }
+        if features.segment_count > 0 {
+            capacity += 2 + 2 * features.segment_count;
+        }
+        if features.compressed {
+            capacity += COMPRESSION_OVERHEAD;
+        }
+        capacity += features.encrypted_regions * Self::REGION_HEADER_BYTES;
+        capacity
+    }
+}
+
+/// A typed view over payload bytes, so fixed-layout structs can be stored in a payload
+/// without manual offset arithmetic.
+pub trait PayloadCodec: Sized {
+    /// Encodes `self` into a payload.
+    fn encode(&self) -> Payload;
+
+    /// Decodes `self` from the leading bytes of a payload, validating that the payload
+    /// is long enough for the target type.
+    fn decode(payload: &Payload) -> Result<Self, DPCError>;
+}
+
+impl<const N: usize> PayloadCodec for [u8; N] {
+    fn encode(&self) -> Payload {
+        Payload::from_bytes(self)
+    }
+
+    fn decode(payload: &Payload) -> Result<Self, DPCError> {
+        payload.require_len(N)?;
+        let mut bytes = [0u8; N];
+        bytes.copy_from_slice(&payload.as_ref()[..N]);
+        Ok(bytes)
+    }
+}
+
+impl PayloadCodec for u32 {
+    fn encode(&self) -> Payload {
+        Payload::from_bytes(&self.to_le_bytes())
+    }
+
+    fn decode(payload: &Payload) -> Result<Self, DPCError> {
+        Ok(u32::from_le_bytes(<[u8; 4]>::decode(payload)?))
+    }
+}
+
+impl PayloadCodec for u64 {
+    fn encode(&self) -> Payload {
+        Payload::from_bytes(&self.to_le_bytes())
+    }
+
+    fn decode(payload: &Payload) -> Result<Self, DPCError> {
+        Ok(u64::from_le_bytes(<[u8; 8]>::decode(payload)?))
+    }
+}
+
+impl AsRef<[u8]> for Payload {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
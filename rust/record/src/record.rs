@@ -0,0 +1,182 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{errors::DPCError, payload::Payload};
+
+use snarkvm_curves::{
+    bw6_761::BW6_761,
+    edwards_bls12::EdwardsParameters,
+    traits::{ModelParameters, PairingEngine},
+};
+use snarkvm_utilities::{to_bytes, FromBytes, ToBytes};
+
+/// The constraint field of the inner circuit, i.e. the base field of the encoding group.
+pub type InnerField = <EdwardsParameters as ModelParameters>::BaseField;
+
+/// The constraint field of the outer circuit, which program identifiers are elements of.
+pub type OuterField = <BW6_761 as PairingEngine>::Fr;
+
+/// The nonce used to derive a record's serial number.
+pub type SerialNumberNonce = InnerField;
+
+/// The randomness used to compute a record's commitment.
+pub type CommitmentRandomness = <EdwardsParameters as ModelParameters>::ScalarField;
+
+/// The accessors required of a record for encoding.
+pub trait RecordInterface {
+    type Value: Copy + Default + FromBytes + ToBytes;
+
+    fn owner(&self) -> &[u8];
+
+    fn value(&self) -> Self::Value;
+
+    fn payload(&self) -> &Payload;
+
+    fn birth_program_id(&self) -> &[u8];
+
+    fn death_program_id(&self) -> &[u8];
+
+    fn serial_number_nonce(&self) -> &SerialNumberNonce;
+
+    fn commitment(&self) -> &[u8];
+
+    fn commitment_randomness(&self) -> &CommitmentRandomness;
+}
+
+/// An Aleo record.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    pub owner: Vec<u8>,
+    pub value: u64,
+    pub payload: Payload,
+    pub birth_program_id: Vec<u8>,
+    pub death_program_id: Vec<u8>,
+    pub serial_number_nonce: SerialNumberNonce,
+    pub commitment: Vec<u8>,
+    pub commitment_randomness: CommitmentRandomness,
+}
+
+impl Record {
+    /// Returns `true` if this record belongs to the given owner address bytes.
+    ///
+    /// This crate does not carry view keys, so ownership is checked by comparing the
+    /// record's owner field directly; a wallet scanner derives the address bytes from
+    /// its view key before calling this.
+    pub fn is_owned_by(&self, owner: &[u8]) -> bool {
+        self.owner == owner
+    }
+
+    /// Serializes the full record, including the owner and commitment that
+    /// `RecordEncoder::serialize` drops, into its canonical little-endian bytes.
+    ///
+    /// The field order is: owner, value, payload, birth program id, death program id,
+    /// serial number nonce, commitment, commitment randomness. Variable-length fields
+    /// are prefixed with their byte length as a little-endian `u32`; the field elements
+    /// use their `ToBytes` form. The encoding is lossless and inverted by `read_le`.
+    pub fn to_bytes_le(&self) -> Result<Vec<u8>, DPCError> {
+        let mut bytes = vec![];
+        write_var_bytes(&mut bytes, &self.owner);
+        bytes.extend_from_slice(&self.value.to_le_bytes());
+        write_var_bytes(&mut bytes, self.payload.as_ref());
+        write_var_bytes(&mut bytes, &self.birth_program_id);
+        write_var_bytes(&mut bytes, &self.death_program_id);
+        bytes.extend_from_slice(&to_bytes![self.serial_number_nonce]?);
+        write_var_bytes(&mut bytes, &self.commitment);
+        bytes.extend_from_slice(&to_bytes![self.commitment_randomness]?);
+        Ok(bytes)
+    }
+
+    /// Recovers a record from the canonical bytes produced by `to_bytes_le`.
+    pub fn read_le(bytes: &[u8]) -> Result<Record, DPCError> {
+        let mut reader = bytes;
+        let owner = read_var_bytes(&mut reader)?;
+        let value = u64::read(&mut reader)?;
+        let payload = Payload::from_bytes(&read_var_bytes(&mut reader)?);
+        let birth_program_id = read_var_bytes(&mut reader)?;
+        let death_program_id = read_var_bytes(&mut reader)?;
+        let serial_number_nonce = SerialNumberNonce::read(&mut reader)?;
+        let commitment = read_var_bytes(&mut reader)?;
+        let commitment_randomness = CommitmentRandomness::read(&mut reader)?;
+
+        Ok(Record {
+            owner,
+            value,
+            payload,
+            birth_program_id,
+            death_program_id,
+            serial_number_nonce,
+            commitment,
+            commitment_randomness,
+        })
+    }
+}
+
+/// Writes a variable-length field as a little-endian `u32` length prefix and its bytes.
+fn write_var_bytes(bytes: &mut Vec<u8>, field: &[u8]) {
+    bytes.extend_from_slice(&(field.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(field);
+}
+
+/// Reads a variable-length field written by `write_var_bytes`, advancing the reader.
+fn read_var_bytes(reader: &mut &[u8]) -> Result<Vec<u8>, DPCError> {
+    let len = u32::read(&mut *reader)? as usize;
+    if reader.len() < len {
+        return Err(DPCError::Message(format!(
+            "the record bytes end {} bytes into a {}-byte field",
+            reader.len(),
+            len
+        )));
+    }
+    let (field, rest) = reader.split_at(len);
+    *reader = rest;
+    Ok(field.to_vec())
+}
+
+impl RecordInterface for Record {
+    type Value = u64;
+
+    fn owner(&self) -> &[u8] {
+        &self.owner
+    }
+
+    fn value(&self) -> Self::Value {
+        self.value
+    }
+
+    fn payload(&self) -> &Payload {
+        &self.payload
+    }
+
+    fn birth_program_id(&self) -> &[u8] {
+        &self.birth_program_id
+    }
+
+    fn death_program_id(&self) -> &[u8] {
+        &self.death_program_id
+    }
+
+    fn serial_number_nonce(&self) -> &SerialNumberNonce {
+        &self.serial_number_nonce
+    }
+
+    fn commitment(&self) -> &[u8] {
+        &self.commitment
+    }
+
+    fn commitment_randomness(&self) -> &CommitmentRandomness {
+        &self.commitment_randomness
+    }
+}
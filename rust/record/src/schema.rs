@@ -0,0 +1,51 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::decoded::DecodedRecord;
+
+use schemars::JsonSchema;
+
+/// The JSON shape of a [`DecodedRecord`], for schema generation.
+///
+/// The record's field-element types have no JSON representation of their own, so this
+/// mirror spells out the wire encoding the CLI and plaintext formats use: the value as
+/// a number, and every byte field as a `0x`-prefixed lowercase hex string of its
+/// canonical little-endian bytes.
+#[derive(JsonSchema)]
+#[schemars(rename = "DecodedRecord")]
+pub struct DecodedRecordSchema {
+    /// The record value in gates.
+    pub value: u64,
+    /// The payload bytes as a `0x`-prefixed hex string.
+    pub payload: String,
+    /// The birth program id bytes as a `0x`-prefixed hex string.
+    pub birth_program_id: String,
+    /// The death program id bytes as a `0x`-prefixed hex string.
+    pub death_program_id: String,
+    /// The serial number nonce as a `0x`-prefixed hex string of its field bytes.
+    pub serial_number_nonce: String,
+    /// The commitment randomness as a `0x`-prefixed hex string of its scalar bytes.
+    pub commitment_randomness: String,
+}
+
+impl DecodedRecord {
+    /// Returns the JSON Schema describing the record's JSON shape, as a pretty-printed
+    /// JSON string, for generating client-side validators and types.
+    pub fn json_schema_string() -> String {
+        let schema = schemars::schema_for!(DecodedRecordSchema);
+        serde_json::to_string_pretty(&schema).expect("the schema always serializes to JSON")
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+    decoded::DecodedRecord,
+    encoder::{Group, RecordEncoder},
+    errors::DPCError,
+};
+
+use snarkvm_curves::traits::ProjectiveCurve;
+
+/// The output of `RecordEncoder::serialize_into`: the serialized group elements together
+/// with the sign bit of the final element.
+///
+/// This gives the serialized form a proper type identity in place of the anonymous
+/// `(Vec<Group>, bool)` tuple, so the sign bit cannot be separated from its elements or
+/// paired with the wrong record.
+#[derive(Clone, Debug)]
+pub struct SerializedRecord {
+    pub elements: Vec<Group>,
+    pub final_sign_high: bool,
+}
+
+impl SerializedRecord {
+    /// Decodes this serialized record.
+    pub fn into_decoded(self) -> Result<DecodedRecord, DPCError> {
+        RecordEncoder::deserialize(&self.elements, self.final_sign_high)
+    }
+}
+
+impl PartialEq for SerializedRecord {
+    /// Compares element-wise in affine form, so differing projective representations of
+    /// the same record compare equal.
+    fn eq(&self, other: &Self) -> bool {
+        self.final_sign_high == other.final_sign_high
+            && self.elements.len() == other.elements.len()
+            && self
+                .elements
+                .iter()
+                .zip(other.elements.iter())
+                .all(|(left, right)| left.into_affine() == right.into_affine())
+    }
+}
+
+impl Eq for SerializedRecord {}
@@ -0,0 +1,62 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Deterministic record fixtures for tests, behind the `test-helpers` feature, so every
+//! crate depending on this one can share them instead of duplicating the boilerplate
+//! for program ids, nonces, and randomness.
+
+use crate::{
+    encoder::Affine,
+    payload::Payload,
+    record::{CommitmentRandomness, OuterField, Record, SerialNumberNonce},
+};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaChaRng;
+use snarkvm_curves::traits::AffineCurve;
+use snarkvm_utilities::{to_bytes, ToBytes, UniformRand};
+
+/// Returns a deterministic record with an empty payload, with every field derived from
+/// the seed. The same seed always yields the same record, and the record is guaranteed
+/// to be accepted by `RecordEncoder::serialize`.
+pub fn dummy_record(seed: u64) -> Record {
+    dummy_record_with_payload(seed, &[])
+}
+
+/// Returns a deterministic record carrying the given payload bytes, with every other
+/// field derived from the seed.
+pub fn dummy_record_with_payload(seed: u64, payload: &[u8]) -> Record {
+    let rng = &mut ChaChaRng::seed_from_u64(seed);
+
+    // Sample a nonce whose bytes recover to a group element, so `serialize` accepts it.
+    let serial_number_nonce = loop {
+        let candidate = SerialNumberNonce::rand(rng);
+        if Affine::from_random_bytes(&to_bytes![candidate].unwrap()).is_some() {
+            break candidate;
+        }
+    };
+
+    Record {
+        owner: vec![0u8; 32],
+        value: rng.gen(),
+        payload: Payload::from_bytes(payload),
+        birth_program_id: to_bytes![OuterField::rand(rng)].unwrap(),
+        death_program_id: to_bytes![OuterField::rand(rng)].unwrap(),
+        serial_number_nonce,
+        commitment: vec![0u8; 32],
+        commitment_randomness: CommitmentRandomness::rand(rng),
+    }
+}